@@ -4,7 +4,9 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::settings_backup::BackupTarget;
 
 /// Represents a single entry in a Claude session JSONL file
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,19 +21,199 @@ pub struct SessionEntry {
     #[serde(rename = "sessionId")]
     pub session_id: Option<String>,
     pub version: Option<String>,
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default)]
     pub message_type: String,
     pub message: Option<Message>,
     pub uuid: Option<String>,
     pub timestamp: Option<String>,
+    #[serde(rename = "isMeta", skip_serializing_if = "Option::is_none")]
+    pub is_meta: Option<bool>,
+    /// Generated session title (only on `summary` entries)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// UUID of the last message covered by a summary (only on `summary` entries)
+    #[serde(rename = "leafUuid", skip_serializing_if = "Option::is_none")]
+    pub leaf_uuid: Option<String>,
+    /// Subtype of `system` entries, e.g. `compact_boundary`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtype: Option<String>,
+    #[serde(rename = "compactMetadata", skip_serializing_if = "Option::is_none")]
+    pub compact_metadata: Option<CompactMetadata>,
+    /// Set on the user message carrying the summary injected after a compaction
+    #[serde(rename = "isCompactSummary", skip_serializing_if = "Option::is_none")]
+    pub is_compact_summary: Option<bool>,
+}
+
+/// Metadata attached to a `compact_boundary` system entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactMetadata {
+    /// What triggered the compaction: `auto` or `manual`
+    pub trigger: Option<String>,
+    /// Context size in tokens right before compaction
+    #[serde(rename = "preTokens")]
+    pub pre_tokens: Option<u64>,
+}
+
+/// Classification of a session entry based on its `type` field
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EntryKind {
+    User,
+    Assistant,
+    Summary,
+    System,
+    FileHistorySnapshot,
+    Unknown(String),
+}
+
+impl EntryKind {
+    /// Classify an entry by the raw value of its `type` field
+    pub fn from_type(message_type: &str) -> Self {
+        match message_type {
+            "user" => EntryKind::User,
+            "assistant" => EntryKind::Assistant,
+            "summary" => EntryKind::Summary,
+            "system" => EntryKind::System,
+            "file-history-snapshot" => EntryKind::FileHistorySnapshot,
+            other => EntryKind::Unknown(other.to_string()),
+        }
+    }
+
+    /// Label used when reporting entry counts
+    pub fn label(&self) -> &str {
+        match self {
+            EntryKind::User => "user",
+            EntryKind::Assistant => "assistant",
+            EntryKind::Summary => "summary",
+            EntryKind::System => "system",
+            EntryKind::FileHistorySnapshot => "file-history-snapshot",
+            EntryKind::Unknown(_) => "unknown",
+        }
+    }
+}
+
+impl SessionEntry {
+    /// Get the kind of this entry
+    pub fn kind(&self) -> EntryKind {
+        EntryKind::from_type(&self.message_type)
+    }
 }
 
 /// Represents a message in the Claude session
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawMessage")]
 pub struct Message {
     pub role: String,
-    #[serde(deserialize_with = "deserialize_content")]
+    /// Text of the message, with text blocks joined by newlines
     pub content: String,
+    /// Structured content blocks when the content is an array
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<ContentBlock>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+/// Message as written in the JSONL file, before content is split into text and blocks
+#[derive(Deserialize)]
+struct RawMessage {
+    #[serde(default)]
+    role: String,
+    content: Value,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+impl TryFrom<RawMessage> for Message {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawMessage) -> Result<Self, Self::Error> {
+        // Unrecognized or malformed blocks are skipped rather than failing the message
+        let blocks = match &raw.content {
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|item| serde_json::from_value(item.clone()).ok())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(Message {
+            role: raw.role,
+            content: deserialize_content(raw.content)?,
+            blocks,
+            id: raw.id,
+            usage: raw.usage,
+        })
+    }
+}
+
+/// A structured block inside message content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        #[serde(default)]
+        content: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        is_error: Option<bool>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+impl ContentBlock {
+    /// Text carried by a text block or a tool result
+    ///
+    /// Tool result content may be a plain string or an array of text blocks.
+    pub fn text(&self) -> Option<String> {
+        match self {
+            ContentBlock::Text { text } => Some(text.clone()),
+            ContentBlock::ToolResult { content, .. } => match content {
+                Value::String(text) => Some(text.clone()),
+                Value::Array(items) => Some(
+                    items
+                        .iter()
+                        .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Token usage reported on assistant messages
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+}
+
+impl Usage {
+    /// Estimated size of the context window sent with this request
+    pub fn context_tokens(&self) -> u64 {
+        self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens
+    }
 }
 
 /// Custom deserializer for message content that can be either a string or an array
@@ -88,23 +270,188 @@ where
 }
 
 /// Represents a single hook configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Hook {
     #[serde(rename = "type")]
     pub hook_type: String,
+    /// Shell command run by `command` hooks; other hook types may not have one
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub command: String,
+    /// Timeout in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+
+    // Preserve fields of other hook types and future additions
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+impl Hook {
+    /// Create a `command` hook without timeout
+    pub fn new_command(command: impl Into<String>) -> Self {
+        Hook {
+            hook_type: "command".to_string(),
+            command: command.into(),
+            timeout: None,
+            other: HashMap::new(),
+        }
+    }
 }
 
 /// Represents a hook matcher with its associated hooks
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HookMatcher {
+    /// Events without matchers may omit this field entirely
+    #[serde(default)]
     pub matcher: String,
     pub hooks: Vec<Hook>,
+
+    // Preserve any other fields on the matcher
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+impl HookMatcher {
+    /// Check whether this matcher fires for a value (tool name, compaction trigger, ...)
+    ///
+    /// An empty matcher or `*` matches everything. Otherwise the matcher is a
+    /// regex that must match the whole value, so `Edit` matches only `Edit`
+    /// while `Edit|Write` and `Notebook.*` behave as expected.
+    pub fn matches(&self, value: &str) -> bool {
+        let pattern = self.matcher.trim();
+        if pattern.is_empty() || pattern == "*" {
+            return true;
+        }
+
+        match regex::Regex::new(&format!("^(?:{pattern})$")) {
+            Ok(re) => re.is_match(value),
+            Err(_) => pattern == value,
+        }
+    }
+}
+
+/// Hook events supported by Claude Code
+///
+/// Events claco does not know about are kept as `Unknown` so they survive a
+/// load/save round-trip untouched.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HookEvent {
+    PreToolUse,
+    PostToolUse,
+    Notification,
+    UserPromptSubmit,
+    Stop,
+    SubagentStop,
+    PreCompact,
+    SessionStart,
+    SessionEnd,
+    Unknown(String),
+}
+
+impl HookEvent {
+    /// All events known to claco
+    pub const ALL: [HookEvent; 9] = [
+        HookEvent::PreToolUse,
+        HookEvent::PostToolUse,
+        HookEvent::Notification,
+        HookEvent::UserPromptSubmit,
+        HookEvent::Stop,
+        HookEvent::SubagentStop,
+        HookEvent::PreCompact,
+        HookEvent::SessionStart,
+        HookEvent::SessionEnd,
+    ];
+
+    /// Get the event name as written in settings.json
+    pub fn as_str(&self) -> &str {
+        match self {
+            HookEvent::PreToolUse => "PreToolUse",
+            HookEvent::PostToolUse => "PostToolUse",
+            HookEvent::Notification => "Notification",
+            HookEvent::UserPromptSubmit => "UserPromptSubmit",
+            HookEvent::Stop => "Stop",
+            HookEvent::SubagentStop => "SubagentStop",
+            HookEvent::PreCompact => "PreCompact",
+            HookEvent::SessionStart => "SessionStart",
+            HookEvent::SessionEnd => "SessionEnd",
+            HookEvent::Unknown(name) => name,
+        }
+    }
+
+    /// Parse an event name, keeping unrecognized names as `Unknown`
+    pub fn from_name(name: &str) -> Self {
+        HookEvent::ALL
+            .iter()
+            .find(|event| event.as_str() == name)
+            .cloned()
+            .unwrap_or_else(|| HookEvent::Unknown(name.to_string()))
+    }
+
+    /// Whether Claude Code filters this event by a matcher
+    ///
+    /// Tool events match on tool name, `PreCompact` on `manual`/`auto` and
+    /// `SessionStart` on its source; all other events ignore the matcher.
+    pub fn uses_matcher(&self) -> bool {
+        matches!(
+            self,
+            HookEvent::PreToolUse
+                | HookEvent::PostToolUse
+                | HookEvent::PreCompact
+                | HookEvent::SessionStart
+        )
+    }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, HookEvent::Unknown(_))
+    }
+}
+
+impl std::fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Strict parsing used for user input: unknown events are rejected
+impl std::str::FromStr for HookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match HookEvent::from_name(s) {
+            HookEvent::Unknown(name) => {
+                let valid: Vec<&str> = HookEvent::ALL.iter().map(|e| e.as_str()).collect();
+                Err(format!(
+                    "invalid event '{name}' - valid events are: {}",
+                    valid.join(", ")
+                ))
+            }
+            event => Ok(event),
+        }
+    }
+}
+
+impl Serialize for HookEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for HookEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        Ok(HookEvent::from_name(&name))
+    }
 }
 
 /// Represents the hooks section in settings.json
-/// This is directly a HashMap of event names to matchers
-pub type Hooks = HashMap<String, Vec<HookMatcher>>;
+/// This is directly a HashMap of events to matchers
+pub type Hooks = HashMap<HookEvent, Vec<HookMatcher>>;
 
 /// Represents a Claude settings.json file
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -170,6 +517,30 @@ pub fn project_local_settings_path() -> PathBuf {
     PathBuf::from(".claude").join("settings.local.json")
 }
 
+/// Get the path to enterprise managed settings
+pub fn managed_settings_path() -> PathBuf {
+    if cfg!(target_os = "macos") {
+        PathBuf::from("/Library/Application Support/ClaudeCode/managed-settings.json")
+    } else if cfg!(windows) {
+        PathBuf::from(r"C:\ProgramData\ClaudeCode\managed-settings.json")
+    } else {
+        PathBuf::from("/etc/claude-code/managed-settings.json")
+    }
+}
+
+/// Get every settings file Claude Code reads, highest precedence first
+///
+/// Managed settings override project-local settings, which override shared
+/// project settings, which override user settings.
+pub fn settings_files_by_precedence() -> Result<Vec<(&'static str, PathBuf)>> {
+    Ok(vec![
+        ("managed", managed_settings_path()),
+        ("project.local", project_local_settings_path()),
+        ("project", project_settings_path()),
+        ("user", user_settings_path()?),
+    ])
+}
+
 /// Load settings from a file path
 pub fn load_settings(path: &PathBuf) -> anyhow::Result<Settings> {
     use anyhow::Context;
@@ -182,8 +553,8 @@ pub fn load_settings(path: &PathBuf) -> anyhow::Result<Settings> {
         .with_context(|| format!("Failed to read settings file: {}", path.display()))?;
 
     // Try to parse as-is first
-    match serde_json::from_str::<Settings>(&content) {
-        Ok(settings) => Ok(settings),
+    let settings = match serde_json::from_str::<Settings>(&content) {
+        Ok(settings) => settings,
         Err(_) => {
             // Try to parse as raw JSON and migrate old format
             let mut value: Value = serde_json::from_str(&content).with_context(|| {
@@ -232,13 +603,70 @@ pub fn load_settings(path: &PathBuf) -> anyhow::Result<Settings> {
 
             // Now try to parse the migrated value
             serde_json::from_value(value)
-                .with_context(|| format!("Failed to parse settings from: {}", path.display()))
+                .with_context(|| format!("Failed to parse settings from: {}", path.display()))?
+        }
+    };
+
+    warn_unknown_hook_events(path, &settings);
+
+    Ok(settings)
+}
+
+/// Warn about hook events Claude Code does not recognize
+fn warn_unknown_hook_events(path: &Path, settings: &Settings) {
+    if let Some(hooks) = &settings.hooks {
+        let mut unknown: Vec<&str> = hooks
+            .keys()
+            .filter(|event| !event.is_known())
+            .map(|event| event.as_str())
+            .collect();
+        unknown.sort();
+        for event in unknown {
+            eprintln!(
+                "warning: unknown hook event '{event}' in {}",
+                path.display()
+            );
         }
     }
 }
 
 /// Save settings to a file path with atomic operations
-pub fn save_settings(path: &PathBuf, settings: &Settings) -> anyhow::Result<()> {
+pub fn save_settings(path: &Path, settings: &Settings) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    // Serialize to JSON first to validate
+    let content =
+        serde_json::to_string_pretty(settings).context("Failed to serialize settings to JSON")?;
+
+    write_settings_file(path, content.as_bytes())
+}
+
+/// Save settings like [`save_settings`], first snapshotting the current file into `backup`
+///
+/// A failed backup is reported as a warning and does not stop the save.
+pub fn save_settings_with_backup(
+    path: &Path,
+    settings: &Settings,
+    backup: Option<&BackupTarget>,
+) -> anyhow::Result<()> {
+    if let Some(backup) = backup {
+        if let Err(e) = backup.snapshot(path) {
+            eprintln!(
+                "warning: failed to back up {} before saving: {e:#}",
+                path.display()
+            );
+        }
+    }
+    save_settings(path, settings)
+}
+
+/// Save settings, backing up the current file as claco's config asks
+pub fn save_settings_backed_up(path: &Path, settings: &Settings) -> anyhow::Result<()> {
+    save_settings_with_backup(path, settings, BackupTarget::configured().as_ref())
+}
+
+/// Replace a settings file with `content` atomically, creating parent directories
+pub fn write_settings_file(path: &Path, content: &[u8]) -> anyhow::Result<()> {
     use anyhow::Context;
 
     // Ensure parent directory exists
@@ -247,10 +675,6 @@ pub fn save_settings(path: &PathBuf, settings: &Settings) -> anyhow::Result<()>
             .with_context(|| format!("Failed to create parent directory: {}", parent.display()))?;
     }
 
-    // Serialize to JSON first to validate
-    let content =
-        serde_json::to_string_pretty(settings).context("Failed to serialize settings to JSON")?;
-
     // Create a temporary file in the same directory
     let temp_path = path.with_extension("tmp");
 
@@ -265,7 +689,7 @@ pub fn save_settings(path: &PathBuf, settings: &Settings) -> anyhow::Result<()>
             .with_context(|| format!("Failed to create temporary file: {}", temp_path.display()))?;
 
         temp_file
-            .write_all(content.as_bytes())
+            .write_all(content)
             .context("Failed to write settings to temporary file")?;
 
         temp_file
@@ -337,6 +761,83 @@ mod tests {
         assert_eq!(serialized["customField"], json!("test"));
     }
 
+    #[test]
+    fn test_hook_events_round_trip() {
+        use serde_json::json;
+
+        let json_str = r#"{
+            "hooks": {
+                "PostToolUse": [{"matcher": "Edit", "hooks": [{"type": "command", "command": "fmt"}]}],
+                "Stop": [{"hooks": [{"type": "command", "command": "notify"}]}],
+                "FutureEvent": [{"matcher": "", "hooks": []}]
+            }
+        }"#;
+
+        let settings: Settings = serde_json::from_str(json_str).unwrap();
+        let hooks = settings.hooks.as_ref().unwrap();
+        assert!(hooks.contains_key(&HookEvent::PostToolUse));
+        assert_eq!(hooks[&HookEvent::Stop][0].matcher, "");
+        assert!(hooks.contains_key(&HookEvent::Unknown("FutureEvent".to_string())));
+
+        // Unknown events are preserved on save
+        let serialized = serde_json::to_value(&settings).unwrap();
+        assert_eq!(
+            serialized["hooks"]["FutureEvent"],
+            json!([{"matcher": "", "hooks": []}])
+        );
+
+        // User input is parsed strictly
+        assert_eq!("SessionEnd".parse::<HookEvent>(), Ok(HookEvent::SessionEnd));
+        assert!("ToolPattern".parse::<HookEvent>().is_err());
+        assert!(HookEvent::PreCompact.uses_matcher());
+        assert!(!HookEvent::Stop.uses_matcher());
+    }
+
+    #[test]
+    fn test_hook_preserves_timeout_and_unknown_fields() {
+        use serde_json::json;
+
+        let hooks_json = json!({
+            "PreToolUse": [{
+                "matcher": "Bash",
+                "hooks": [
+                    {"type": "command", "command": "guard.sh", "timeout": 30},
+                    {"type": "prompt", "prompt": "Is this safe?", "model": "haiku"}
+                ],
+                "description": "safety"
+            }]
+        });
+
+        let settings: Settings =
+            serde_json::from_value(json!({ "hooks": hooks_json.clone() })).unwrap();
+        let matcher = &settings.hooks.as_ref().unwrap()[&HookEvent::PreToolUse][0];
+        assert_eq!(matcher.hooks[0].timeout, Some(30));
+        assert_eq!(matcher.hooks[1].hook_type, "prompt");
+        assert_eq!(matcher.hooks[1].command, "");
+        assert_eq!(matcher.hooks[1].other["prompt"], json!("Is this safe?"));
+
+        let serialized = serde_json::to_value(&settings).unwrap();
+        assert_eq!(serialized["hooks"], hooks_json);
+    }
+
+    #[test]
+    fn test_hook_matcher_matches() {
+        let matcher = |pattern: &str| HookMatcher {
+            matcher: pattern.to_string(),
+            hooks: Vec::new(),
+            other: HashMap::new(),
+        };
+
+        assert!(matcher("").matches("Bash"));
+        assert!(matcher("*").matches("Bash"));
+        assert!(matcher("Edit").matches("Edit"));
+        assert!(!matcher("Edit").matches("MultiEdit"));
+        assert!(matcher("Edit|Write").matches("Write"));
+        assert!(matcher("mcp__github__.*").matches("mcp__github__create_issue"));
+        // Invalid regex falls back to exact comparison
+        assert!(matcher("Bash(").matches("Bash("));
+    }
+
     #[test]
     fn test_save_and_load_settings() {
         use serde_json::json;
@@ -395,6 +896,37 @@ mod tests {
         assert!(settings_path.exists());
     }
 
+    #[test]
+    fn test_save_settings_with_backup() {
+        use crate::settings_backup::list_snapshots;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let settings_path = dir.path().join("settings.json");
+        fs::write(&settings_path, "{ not json").unwrap();
+
+        let target = BackupTarget {
+            data_dir: dir.path().join("data"),
+            keep: 5,
+        };
+        save_settings_with_backup(&settings_path, &Settings::default(), Some(&target)).unwrap();
+
+        // The previous bytes are kept verbatim, even when they do not parse
+        let snapshots = list_snapshots(&target.dir_for(&settings_path).unwrap()).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(
+            fs::read_to_string(&snapshots[0].path).unwrap(),
+            "{ not json"
+        );
+
+        // A backup that cannot be written does not stop the save
+        let blocked = BackupTarget {
+            data_dir: settings_path.clone(),
+            keep: 5,
+        };
+        save_settings_with_backup(&settings_path, &Settings::default(), Some(&blocked)).unwrap();
+    }
+
     #[test]
     fn test_load_corrupted_json() {
         use tempfile::tempdir;
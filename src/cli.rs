@@ -1,4 +1,8 @@
+use crate::claude::{
+    project_local_settings_path, project_settings_path, user_settings_path, HookEvent,
+};
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 /// `claco` (Claude Code Helper) is a CLI tool for boosting Claude Code productive.
 #[derive(Parser)]
@@ -34,39 +38,127 @@ pub enum Commands {
         session: Option<String>,
     },
     /// Display session info by ID (defaults to most recent session)
+    #[command(args_conflicts_with_subcommands = true)]
     Session {
+        #[command(subcommand)]
+        action: Option<SessionAction>,
         /// Session ID to display (if not provided, shows most recent session)
         session_id: Option<String>,
     },
     /// List all projects with their sessions
     Projects,
+    /// PreToolUse hook handler that blocks or allows tool calls by policy
+    Guard {
+        /// Policy file (defaults to .claude/guard.json, then ~/.claude/guard.json)
+        #[arg(long)]
+        policy: Option<PathBuf>,
+        /// Print the policy in effect instead of reading a hook payload
+        #[arg(long)]
+        show_policy: bool,
+    },
+    /// Run a hook command and record it in the audit log (use as the hook command)
+    #[command(name = "hook-exec")]
+    HookExec {
+        /// Matcher the hook is registered under, recorded in the log
+        #[arg(long)]
+        matcher: Option<String>,
+        /// Kill the command after this many seconds (defaults to 60)
+        #[arg(long)]
+        timeout: Option<u64>,
+        /// Command to run, after `--`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Notification/Stop hook handler that forwards a message to the sinks in claco's config
+    Notify {
+        /// Message to send instead of the one derived from the hook payload
+        #[arg(long)]
+        message: Option<String>,
+        /// Send a test notification without reading a hook payload
+        #[arg(long)]
+        test: bool,
+    },
     /// Manage Claude Code settings
     #[command(subcommand)]
     Settings(SettingsSubcommand),
 }
 
+#[derive(Subcommand)]
+pub enum SessionAction {
+    /// Validate transcript entries and report lines that fail to parse
+    Validate {
+        /// Session ID to validate (defaults to most recent session)
+        session_id: Option<String>,
+        /// Validate every session of every project
+        #[arg(long, conflicts_with = "session_id")]
+        all: bool,
+    },
+    /// List tool errors, rejected tool calls and interrupted requests
+    Errors {
+        /// Session ID to inspect (defaults to most recent session)
+        session_id: Option<String>,
+        /// Inspect every session of the current project
+        #[arg(long, conflicts_with = "session_id")]
+        project: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum HooksAction {
     /// List all hooks
     List {
-        /// Scope to list hooks from (user or project, defaults to showing both)
-        #[arg(long)]
-        scope: Option<String>,
+        /// Scope to list hooks from (defaults to showing all scopes)
+        #[arg(long, value_enum)]
+        scope: Option<Scope>,
+        /// Show the merged hooks from every scope in precedence order, flagging duplicates
+        #[arg(long, conflicts_with = "scope")]
+        effective: bool,
     },
     /// Add a new hook
     Add {
-        /// Scope to add hook to (user or project)
-        #[arg(long, default_value = "project")]
-        scope: String,
-        /// Event type to hook into
+        /// Scope to add hook to
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
+        /// Event type to hook into (PreToolUse, PostToolUse, Notification, UserPromptSubmit,
+        /// Stop, SubagentStop, PreCompact, SessionStart or SessionEnd)
         #[arg(long)]
-        event: String,
-        /// Matcher pattern for the hook (optional, defaults to empty string)
+        event: HookEvent,
+        /// Matcher pattern for the hook (only for PreToolUse, PostToolUse, PreCompact and SessionStart)
         #[arg(long, default_value = "")]
         matcher: String,
-        /// Command to execute when hook is triggered
+        /// Command to execute when hook is triggered (required for command hooks)
+        #[arg(long)]
+        command: Option<String>,
+        /// Hook type
+        #[arg(long = "type", default_value = "command")]
+        hook_type: String,
+        /// Timeout in seconds
         #[arg(long)]
-        command: String,
+        timeout: Option<u64>,
+        /// Extra hook field as KEY=VALUE, VALUE is parsed as JSON or kept as a string (repeatable)
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        fields: Vec<String>,
+    },
+    /// Write a ready-to-edit hook script under .claude/hooks/ and register it
+    Scaffold {
+        /// Event the script handles
+        #[arg(long)]
+        event: HookEvent,
+        /// Matcher pattern for the hook (only for PreToolUse, PostToolUse, PreCompact and SessionStart)
+        #[arg(long, default_value = "")]
+        matcher: String,
+        /// Script language
+        #[arg(long, value_enum, default_value = "bash")]
+        lang: ScriptLang,
+        /// Script file name without extension (defaults to one derived from event and matcher)
+        #[arg(long)]
+        name: Option<String>,
+        /// Scope to register the hook in
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
+        /// Overwrite an existing script
+        #[arg(long)]
+        force: bool,
     },
     /// Delete hooks interactively
     Delete {
@@ -74,6 +166,173 @@ pub enum HooksAction {
         #[arg(long, default_value = "true")]
         interactive: bool,
     },
+    /// Remove a hook by ID (as shown by `hooks list`)
+    #[command(alias = "remove")]
+    Rm {
+        /// Hook ID in the form scope/event/matcher/index (e.g. project/PreToolUse/Bash/0)
+        id: HookId,
+    },
+    /// Disable a hook, parking it so it can be re-enabled later
+    Disable {
+        /// Hook ID in the form scope/event/matcher/index
+        id: HookId,
+    },
+    /// Re-enable a previously disabled hook at its original position
+    Enable {
+        /// Hook ID the hook had when it was disabled
+        id: HookId,
+    },
+    /// Move a hook before another hook with the same scope, event and matcher
+    Move {
+        /// Hook ID to move
+        id: HookId,
+        /// Hook ID to place it before
+        #[arg(long)]
+        before: HookId,
+    },
+    /// Install a built-in hook recipe (lists the recipes when none is given)
+    Install {
+        /// Recipe name (format, protect, notify, log-prompts or guard)
+        recipe: Option<String>,
+        /// Scope to install the recipe into
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
+        /// Recipe parameter as KEY=VALUE (repeatable)
+        #[arg(long = "param", value_name = "KEY=VALUE")]
+        params: Vec<String>,
+    },
+    /// Remove the hook and helper script installed by a recipe
+    Uninstall {
+        /// Recipe name
+        recipe: String,
+        /// Scope the recipe was installed into
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
+    },
+    /// Check matchers and commands in every scope, exiting non-zero on errors
+    Lint {
+        /// Treat warnings as errors
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Show hook invocations recorded by `claco hook-exec`
+    Log {
+        /// Only show invocations for this event
+        #[arg(long)]
+        event: Option<HookEvent>,
+        /// Only show invocations for this tool
+        #[arg(long)]
+        tool: Option<String>,
+        /// Only show failed, timed out or blocking invocations
+        #[arg(long)]
+        failed: bool,
+        /// Number of most recent invocations to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        /// Summarize invocations per command instead of listing them
+        #[arg(long)]
+        stats: bool,
+    },
+    /// Simulate an event and run the hooks that would fire
+    ///
+    /// Hooks get the settings `env` variables, CLAUDECODE, CLAUDE_CODE_ENTRYPOINT
+    /// and CLAUDE_PROJECT_DIR as in a session. Plugin variables and the
+    /// CLAUDE_ENV_FILE of SessionStart hooks are not set.
+    Test {
+        /// Event to simulate
+        #[arg(long)]
+        event: HookEvent,
+        /// Tool name for PreToolUse and PostToolUse (e.g. Bash)
+        #[arg(long)]
+        tool: Option<String>,
+        /// Tool input as JSON (e.g. '{"command":"git status"}')
+        #[arg(long)]
+        input: Option<String>,
+        /// Extra payload fields as a JSON object, overriding the generated ones
+        #[arg(long)]
+        payload: Option<String>,
+    },
+    /// Record real payloads as JSON fixtures until interrupted with Ctrl-C
+    ///
+    /// Installs a temporary hook for the event and restores the original
+    /// settings file when recording stops.
+    Record {
+        /// Event to record
+        #[arg(long)]
+        event: HookEvent,
+        /// Directory to write numbered payload files to
+        #[arg(long)]
+        out: PathBuf,
+        /// Only record events matching this matcher (e.g. Bash)
+        #[arg(long, default_value = "")]
+        matcher: String,
+        /// Scope to install the recording hook into
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
+    },
+    /// Save the payload on stdin to a fixture directory (run by `hooks record`)
+    #[command(hide = true)]
+    Capture {
+        /// Directory to write the payload file to
+        #[arg(long)]
+        out: PathBuf,
+    },
+}
+
+/// Address of a single hook: `scope/event/matcher/index`
+///
+/// The index counts hooks under the same matcher within an event, starting
+/// at 0. Events without a matcher use an empty segment, e.g. `user/Stop//0`.
+/// Events claco does not know are accepted, as `hooks list` prints IDs for
+/// every event found in settings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookId {
+    pub scope: Scope,
+    pub event: HookEvent,
+    pub matcher: String,
+    pub index: usize,
+}
+
+impl std::fmt::Display for HookId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}/{}",
+            self.scope.label(),
+            self.event,
+            self.matcher,
+            self.index
+        )
+    }
+}
+
+impl std::str::FromStr for HookId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid hook ID '{s}' - expected scope/event/matcher/index");
+
+        // The matcher may itself contain '/', so split from both ends
+        let (scope, rest) = s.split_once('/').ok_or_else(invalid)?;
+        let (event, rest) = rest.split_once('/').ok_or_else(invalid)?;
+        let (matcher, index) = rest.rsplit_once('/').ok_or_else(invalid)?;
+        if event.is_empty() {
+            return Err(invalid());
+        }
+
+        let scope = <Scope as clap::ValueEnum>::from_str(scope, false)
+            .map_err(|_| format!("invalid scope '{scope}' in hook ID '{s}'"))?;
+        let index = index
+            .parse()
+            .map_err(|_| format!("invalid index '{index}' in hook ID '{s}'"))?;
+
+        Ok(HookId {
+            scope,
+            event: HookEvent::from_name(event),
+            matcher: matcher.to_string(),
+            index,
+        })
+    }
 }
 
 #[derive(Subcommand)]
@@ -110,9 +369,11 @@ pub enum CommandsSubcommand {
         #[arg(short, long, default_value = "true")]
         interactive: bool,
     },
+    /// Show slash command usage across sessions and find unused commands
+    Stats,
 }
 
-#[derive(clap::ValueEnum, Clone, Debug)]
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq, Eq)]
 pub enum Scope {
     User,
     Project,
@@ -120,6 +381,57 @@ pub enum Scope {
     ProjectLocal,
 }
 
+impl Scope {
+    /// Get the scope name as accepted on the command line
+    pub fn label(&self) -> &'static str {
+        match self {
+            Scope::User => "user",
+            Scope::Project => "project",
+            Scope::ProjectLocal => "project.local",
+        }
+    }
+
+    /// Get the settings.json path for this scope
+    pub fn settings_path(&self) -> anyhow::Result<PathBuf> {
+        match self {
+            Scope::User => user_settings_path(),
+            Scope::Project => Ok(project_settings_path()),
+            Scope::ProjectLocal => Ok(project_local_settings_path()),
+        }
+    }
+}
+
+/// Language of a script written by `hooks scaffold`
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScriptLang {
+    Bash,
+    Python,
+    /// Single-file Rust run with rust-script
+    RustScript,
+}
+
+impl ScriptLang {
+    /// File extension for scripts in this language
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ScriptLang::Bash => "sh",
+            ScriptLang::Python => "py",
+            ScriptLang::RustScript => "rs",
+        }
+    }
+}
+
+/// How `settings apply` combines arrays present in both files
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArrayStrategy {
+    /// Add entries the target does not have yet
+    Union,
+    /// Add every entry, keeping duplicates
+    Append,
+    /// Use the incoming array as is
+    Replace,
+}
+
 #[derive(Subcommand)]
 pub enum SettingsSubcommand {
     /// Apply settings from a file or URL to Claude Code settings
@@ -132,6 +444,68 @@ pub enum SettingsSubcommand {
         /// Overwrite existing settings (abort by default when duplicates exist)
         #[arg(long, default_value = "false")]
         overwrite: bool,
+        /// How to combine arrays such as permission rules present in both files
+        #[arg(long, value_enum, default_value = "union")]
+        strategy: ArrayStrategy,
+        /// Show the changes and conflicts without writing the settings file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show a scope's settings, or the merged settings with the file each value comes from
+    Show {
+        /// Scope to show (defaults to project)
+        #[arg(long, value_enum)]
+        scope: Option<Scope>,
+        /// Merge managed, project.local, project and user settings by precedence
+        #[arg(long, conflicts_with = "scope")]
+        effective: bool,
+    },
+    /// Show the structural differences between two settings sources
+    Diff {
+        /// First side: a scope (user, project or project.local), a file or a GitHub URL
+        a: String,
+        /// Second side: a scope, a file or a GitHub URL
+        b: String,
+    },
+    /// Print the values matching a JSONPath (e.g. $.env.FOO or $.permissions.allow[*])
+    Get {
+        /// JSONPath to query; a leading `$.` may be omitted
+        path: String,
+        /// Scope to read from
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
+    },
+    /// Set the value at a JSONPath, creating missing objects and arrays along the way
+    Set {
+        /// JSONPath of a single value (e.g. $.env.FOO)
+        path: String,
+        /// New value as JSON; strings need quotes (e.g. '"bar"')
+        value: String,
+        /// Scope to write to
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
+    },
+    /// Remove the value at a JSONPath
+    Unset {
+        /// JSONPath of a single value (e.g. $.env.FOO)
+        path: String,
+        /// Scope to write to
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
+    },
+    /// List the backups taken before a scope's settings were overwritten
+    History {
+        /// Scope to list backups for
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
+    },
+    /// Roll a scope's settings back to a backup
+    Restore {
+        /// Backup ID as shown by `settings history`, or `latest`
+        snapshot: String,
+        /// Scope to restore
+        #[arg(long, value_enum, default_value = "project")]
+        scope: Scope,
     },
 }
 
@@ -169,4 +543,10 @@ pub enum AgentsSubcommand {
         /// The filename for the template (optional, defaults to agent-template.md)
         filename: Option<String>,
     },
+    /// Show subagent usage from session transcripts
+    Stats {
+        /// Include sessions of all projects instead of only the current one
+        #[arg(long)]
+        all: bool,
+    },
 }
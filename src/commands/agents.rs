@@ -1,5 +1,9 @@
 use anyhow::Result;
+use claco::transcript::{
+    find_project_dir_for_cwd, project_dirs, read_transcript, session_files, Transcript,
+};
 use claco::{claude_home, AgentsSubcommand, Scope};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{self, Write};
 use std::process::Command;
@@ -7,9 +11,17 @@ use std::process::Command;
 // Constants
 const MAX_GITHUB_FILE_SIZE: usize = 10 * 1024 * 1024; // 10MB
 
+/// Subagent types provided by Claude Code itself
+const BUILTIN_AGENTS: &[&str] = &[
+    "general-purpose",
+    "statusline-setup",
+    "output-style-setup",
+    "Explore",
+    "Plan",
+];
+
 #[derive(Debug)]
 struct AgentInfo {
-    #[allow(dead_code)]
     name: String,
     description: String,
     #[allow(dead_code)]
@@ -26,6 +38,7 @@ struct AgentInfo {
 /// - Deleting agents interactively
 /// - Cleaning up all agents in a scope
 /// - Generating new agents using Claude
+/// - Reporting subagent usage from session transcripts
 pub async fn handle_agents(cmd: AgentsSubcommand) -> Result<()> {
     match cmd {
         AgentsSubcommand::List { scope } => handle_agents_list(scope)?,
@@ -33,6 +46,7 @@ pub async fn handle_agents(cmd: AgentsSubcommand) -> Result<()> {
         AgentsSubcommand::Delete { interactive } => handle_agents_delete(interactive)?,
         AgentsSubcommand::Clean { scope } => handle_agents_clean(scope)?,
         AgentsSubcommand::Generate { filename } => handle_agents_generate(filename)?,
+        AgentsSubcommand::Stats { all } => handle_agents_stats(all)?,
     }
     Ok(())
 }
@@ -983,6 +997,178 @@ You are a specialized agent for [describe specialization].
     Ok(())
 }
 
+/// Usage of one subagent type across sessions
+#[derive(Debug, Default)]
+struct AgentUsage {
+    calls: usize,
+    errors: usize,
+    sidechain_lengths: Vec<usize>,
+}
+
+/// Subagent usage across sessions, checked against the installed agents
+#[derive(Debug, Default)]
+struct AgentStats {
+    usage: BTreeMap<String, AgentUsage>,
+    /// Installed agents that were never invoked, with their scope
+    unused: Vec<(String, &'static str)>,
+    /// Invoked agents that are neither installed nor built in, with their call count
+    missing: Vec<(String, usize)>,
+}
+
+/// Count `Task` calls per subagent type and match them to installed agents
+///
+/// `installed` maps agent names to the scope they are installed in.
+fn agent_stats(
+    installed: &BTreeMap<String, &'static str>,
+    transcripts: &[Transcript],
+) -> AgentStats {
+    // Subagent transcripts may live in separate files, so match sidechains
+    // to calls by prompt across all sessions
+    let mut sidechain_lengths: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for transcript in transcripts {
+        for chain in transcript.sidechains() {
+            sidechain_lengths
+                .entry(chain.prompt)
+                .or_default()
+                .push(chain.length);
+        }
+    }
+
+    let mut usage: BTreeMap<String, AgentUsage> = BTreeMap::new();
+    for transcript in transcripts {
+        for call in transcript.task_calls() {
+            let agent_usage = usage.entry(call.subagent_type).or_default();
+            agent_usage.calls += 1;
+            if call.failed {
+                agent_usage.errors += 1;
+            }
+            if let Some(lengths) = sidechain_lengths.get_mut(&call.prompt) {
+                if !lengths.is_empty() {
+                    agent_usage.sidechain_lengths.push(lengths.remove(0));
+                }
+            }
+        }
+    }
+
+    let unused = installed
+        .iter()
+        .filter(|(name, _)| !usage.contains_key(name.as_str()))
+        .map(|(name, scope_label)| (name.clone(), *scope_label))
+        .collect();
+    let missing = usage
+        .iter()
+        .filter(|(name, _)| {
+            !installed.contains_key(name.as_str()) && !BUILTIN_AGENTS.contains(&name.as_str())
+        })
+        .map(|(name, agent_usage)| (name.clone(), agent_usage.calls))
+        .collect();
+
+    AgentStats {
+        usage,
+        unused,
+        missing,
+    }
+}
+
+/// Cross-reference `Task` tool calls in transcripts with installed agents
+///
+/// Reports invocation counts, average sidechain length and errors per agent,
+/// installed agents that are never used, and invoked agents that no longer exist.
+fn handle_agents_stats(all: bool) -> Result<()> {
+    // Installed agents are keyed by the `name` in their frontmatter, which is
+    // what Claude Code passes as `subagent_type`
+    let mut installed: BTreeMap<String, &'static str> = BTreeMap::new();
+    for (scope, scope_label) in [(Scope::User, "user"), (Scope::Project, "project")] {
+        let agents_dir = get_agents_dir(&scope)?;
+        if !agents_dir.exists() {
+            continue;
+        }
+
+        let mut agents_list = Vec::new();
+        collect_agents_recursive(&agents_dir, "", &scope, &mut agents_list)?;
+        for (file_name, _, path) in agents_list {
+            let name = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| parse_agent_metadata(&content))
+                .map(|info| info.name)
+                .unwrap_or(file_name);
+            // Project agents override user agents with the same name
+            installed.insert(name, scope_label);
+        }
+    }
+
+    let session_paths = if all {
+        let mut paths = Vec::new();
+        for project_path in project_dirs()? {
+            paths.extend(session_files(&project_path)?);
+        }
+        paths
+    } else {
+        let cwd = std::env::current_dir()?;
+        let cwd_str = cwd.to_string_lossy();
+        match find_project_dir_for_cwd(&cwd_str)? {
+            Some(project_path) => session_files(&project_path)?,
+            None => {
+                println!("No Claude project found for current directory: {cwd_str}");
+                return Ok(());
+            }
+        }
+    };
+
+    let transcripts: Vec<Transcript> = session_paths
+        .iter()
+        .filter_map(|path| read_transcript(path).ok())
+        .collect();
+    let stats = agent_stats(&installed, &transcripts);
+    let usage = &stats.usage;
+
+    println!("Subagent usage ({} session(s)):", transcripts.len());
+    if usage.is_empty() {
+        println!("  No subagent invocations found");
+    }
+
+    let mut sorted: Vec<_> = usage.iter().collect();
+    sorted.sort_by_key(|(_, agent_usage)| std::cmp::Reverse(agent_usage.calls));
+    for (name, agent_usage) in &sorted {
+        let origin = match installed.get(name.as_str()) {
+            Some(scope_label) => *scope_label,
+            None if BUILTIN_AGENTS.contains(&name.as_str()) => "built-in",
+            None => "missing",
+        };
+        let average = if agent_usage.sidechain_lengths.is_empty() {
+            "n/a".to_string()
+        } else {
+            let total: usize = agent_usage.sidechain_lengths.iter().sum();
+            format!(
+                "{:.1}",
+                total as f64 / agent_usage.sidechain_lengths.len() as f64
+            )
+        };
+        println!(
+            "  {} [{}] calls={} avg_sidechain={} errors={}",
+            name, origin, agent_usage.calls, average, agent_usage.errors
+        );
+    }
+
+    if !stats.unused.is_empty() {
+        println!();
+        println!("Installed but never used:");
+        for (name, scope_label) in &stats.unused {
+            println!("  {name} [{scope_label}]");
+        }
+    }
+
+    if !stats.missing.is_empty() {
+        println!();
+        println!("Invoked but not installed:");
+        for (name, calls) in &stats.missing {
+            println!("  {name} ({calls} call(s))");
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1124,4 +1310,48 @@ Content
             assert_eq!(result, expected);
         }
     }
+
+    fn transcript_from(content: &str) -> Transcript {
+        let (entries, failures) = claco::transcript::parse_transcript(content);
+        Transcript {
+            path: std::path::PathBuf::from("session.jsonl"),
+            entries,
+            failures,
+        }
+    }
+
+    #[test]
+    fn test_agent_stats() {
+        let transcripts = [
+            transcript_from(
+                r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Task","input":{"subagent_type":"code-reviewer","prompt":"Review the diff"}},{"type":"tool_use","id":"t2","name":"Task","input":{"subagent_type":"old-agent","prompt":"Do it"}}]}}
+{"type":"user","uuid":"u1","parentUuid":"a1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"LGTM"},{"type":"tool_result","tool_use_id":"t2","is_error":true,"content":"Agent type 'old-agent' not found"}]}}
+"#,
+            ),
+            // A sidechain stored in its own file still counts towards the call
+            transcript_from(
+                r#"{"type":"user","uuid":"s1","parentUuid":null,"isSidechain":true,"message":{"role":"user","content":"Review the diff"}}
+{"type":"assistant","uuid":"s2","parentUuid":"s1","isSidechain":true,"message":{"role":"assistant","content":"Looking"}}
+{"type":"assistant","uuid":"a2","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Task","input":{"subagent_type":"code-reviewer","prompt":"Review again"}},{"type":"tool_use","id":"t4","name":"Task","input":{"subagent_type":"general-purpose","prompt":"Search"}}]}}
+"#,
+            ),
+        ];
+        let installed = BTreeMap::from([
+            ("code-reviewer".to_string(), "project"),
+            ("tester".to_string(), "user"),
+        ]);
+
+        let stats = agent_stats(&installed, &transcripts);
+        let reviewer = &stats.usage["code-reviewer"];
+        assert_eq!(reviewer.calls, 2);
+        assert_eq!(reviewer.errors, 0);
+        assert_eq!(reviewer.sidechain_lengths, vec![2]);
+        assert_eq!(stats.usage["old-agent"].calls, 1);
+        assert_eq!(stats.usage["old-agent"].errors, 1);
+        assert_eq!(stats.usage["general-purpose"].calls, 1);
+
+        assert_eq!(stats.unused, vec![("tester".to_string(), "user")]);
+        // Built-in agents are never reported as missing
+        assert_eq!(stats.missing, vec![("old-agent".to_string(), 1)]);
+    }
 }
@@ -0,0 +1,355 @@
+//! Editing single hooks by ID: `hooks rm`, `disable`, `enable` and `move`
+
+use anyhow::{Context, Result};
+use claco::{
+    load_settings, save_settings_backed_up, Config, Hook, HookEvent, HookId, HookMatcher, Hooks,
+    Scope,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::hooks::format_hook;
+
+/// Pair each hook under an event with its ID
+pub fn hook_ids<'a>(
+    scope: &Scope,
+    event: &HookEvent,
+    matchers: &'a [HookMatcher],
+) -> Vec<(HookId, &'a HookMatcher, &'a Hook)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut ids = Vec::new();
+    for matcher in matchers {
+        for hook in &matcher.hooks {
+            let index = counts.entry(matcher.matcher.as_str()).or_insert(0);
+            ids.push((
+                HookId {
+                    scope: scope.clone(),
+                    event: event.clone(),
+                    matcher: matcher.matcher.clone(),
+                    index: *index,
+                },
+                matcher,
+                hook,
+            ));
+            *index += 1;
+        }
+    }
+    ids
+}
+
+/// Position of the hook at `index` under `matcher`, as (matcher entry, hook) indices
+///
+/// A matcher string may appear in several entries, so the index counts across
+/// all of them in order.
+fn locate_hook(matchers: &[HookMatcher], matcher: &str, index: usize) -> Option<(usize, usize)> {
+    matchers
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.matcher == matcher)
+        .flat_map(|(matcher_idx, entry)| (0..entry.hooks.len()).map(move |i| (matcher_idx, i)))
+        .nth(index)
+}
+
+/// Remove the hook an ID refers to, dropping matcher entries and events left empty
+fn remove_hook(hooks: &mut Hooks, id: &HookId) -> Option<Hook> {
+    let matchers = hooks.get_mut(&id.event)?;
+    let (matcher_idx, hook_idx) = locate_hook(matchers, &id.matcher, id.index)?;
+
+    let hook = matchers[matcher_idx].hooks.remove(hook_idx);
+    // An entry with settings of its own is kept so re-adding a hook restores them
+    let entry = &matchers[matcher_idx];
+    if entry.hooks.is_empty() && entry.other.is_empty() {
+        matchers.remove(matcher_idx);
+    }
+    if matchers.is_empty() {
+        hooks.remove(&id.event);
+    }
+    Some(hook)
+}
+
+/// Insert a hook so it ends up at `index` under `matcher`, appending if the
+/// index is past the end
+pub fn insert_hook(hooks: &mut Hooks, event: &HookEvent, matcher: &str, index: usize, hook: Hook) {
+    let matchers = hooks.entry(event.clone()).or_default();
+    if let Some((matcher_idx, hook_idx)) = locate_hook(matchers, matcher, index) {
+        matchers[matcher_idx].hooks.insert(hook_idx, hook);
+    } else if let Some(entry) = matchers.iter_mut().rev().find(|m| m.matcher == matcher) {
+        entry.hooks.push(hook);
+    } else {
+        matchers.push(HookMatcher {
+            matcher: matcher.to_string(),
+            hooks: vec![hook],
+            other: Default::default(),
+        });
+    }
+}
+
+pub fn handle_hooks_rm(id: &HookId) -> Result<()> {
+    let settings_path = id.scope.settings_path()?;
+    let mut settings = load_settings(&settings_path)?;
+
+    let hook = settings
+        .hooks
+        .as_mut()
+        .and_then(|hooks| remove_hook(hooks, id))
+        .ok_or_else(|| anyhow::anyhow!("hook not found: {id}"))?;
+
+    save_settings_backed_up(&settings_path, &settings)?;
+
+    println!("Removed hook {id}: {}", format_hook_for(id, &hook));
+    println!("Settings file: {}", settings_path.display());
+
+    Ok(())
+}
+
+/// A hook removed from a settings file by `hooks disable`
+#[derive(Debug, Serialize, Deserialize)]
+struct DisabledHook {
+    /// Absolute path of the settings file the hook came from
+    settings_path: PathBuf,
+    scope: String,
+    event: HookEvent,
+    matcher: String,
+    index: usize,
+    hook: Hook,
+    disabled_at: String,
+}
+
+impl DisabledHook {
+    fn id(&self) -> Option<HookId> {
+        format!(
+            "{}/{}/{}/{}",
+            self.scope, self.event, self.matcher, self.index
+        )
+        .parse()
+        .ok()
+    }
+}
+
+/// File in claco's data directory where disabled hooks are parked
+fn disabled_hooks_path() -> Result<PathBuf> {
+    Ok(Config::load()?.data_dir.join("disabled-hooks.json"))
+}
+
+fn load_disabled_hooks() -> Result<Vec<DisabledHook>> {
+    let path = disabled_hooks_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read disabled hooks: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse disabled hooks: {}", path.display()))
+}
+
+fn save_disabled_hooks(disabled: &[DisabledHook]) -> Result<()> {
+    let path = disabled_hooks_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(disabled)?)?;
+    Ok(())
+}
+
+/// Settings path made absolute, so parked hooks remember which project they belong to
+fn absolute_settings_path(scope: &Scope) -> Result<PathBuf> {
+    Ok(std::env::current_dir()?.join(scope.settings_path()?))
+}
+
+pub fn handle_hooks_disable(id: &HookId) -> Result<()> {
+    let settings_path = id.scope.settings_path()?;
+    let mut settings = load_settings(&settings_path)?;
+
+    let hook = settings
+        .hooks
+        .as_mut()
+        .and_then(|hooks| remove_hook(hooks, id))
+        .ok_or_else(|| anyhow::anyhow!("hook not found: {id}"))?;
+    let display = format_hook_for(id, &hook);
+
+    // Park the hook before touching settings so it is never lost
+    let mut disabled = load_disabled_hooks()?;
+    disabled.push(DisabledHook {
+        settings_path: absolute_settings_path(&id.scope)?,
+        scope: id.scope.label().to_string(),
+        event: id.event.clone(),
+        matcher: id.matcher.clone(),
+        index: id.index,
+        hook,
+        disabled_at: chrono::Utc::now().to_rfc3339(),
+    });
+    save_disabled_hooks(&disabled)?;
+    save_settings_backed_up(&settings_path, &settings)?;
+
+    println!("Disabled hook {id}: {display}");
+    println!("Re-enable with: claco hooks enable {id}");
+
+    Ok(())
+}
+
+pub fn handle_hooks_enable(id: &HookId) -> Result<()> {
+    let settings_path = id.scope.settings_path()?;
+    let absolute_path = absolute_settings_path(&id.scope)?;
+
+    // The same ID can be disabled more than once; restore the latest first
+    let mut disabled = load_disabled_hooks()?;
+    let position = disabled
+        .iter()
+        .rposition(|d| d.settings_path == absolute_path && d.id().as_ref() == Some(id))
+        .ok_or_else(|| anyhow::anyhow!("no disabled hook found for {id}"))?;
+    let parked = disabled.remove(position);
+    let display = format_hook_for(id, &parked.hook);
+
+    let mut settings = load_settings(&settings_path)?;
+    insert_hook(
+        settings.hooks.get_or_insert_with(Default::default),
+        &id.event,
+        &id.matcher,
+        id.index,
+        parked.hook,
+    );
+    save_settings_backed_up(&settings_path, &settings)?;
+    save_disabled_hooks(&disabled)?;
+
+    println!("Enabled hook {id}: {display}");
+    println!("Settings file: {}", settings_path.display());
+
+    Ok(())
+}
+
+/// Print parked hooks belonging to the given scopes in the current project
+pub fn print_disabled_hooks(scopes: &[Scope]) -> Result<()> {
+    let mut paths = Vec::new();
+    for scope in scopes {
+        paths.push(absolute_settings_path(scope)?);
+    }
+
+    let disabled: Vec<_> = load_disabled_hooks()?
+        .into_iter()
+        .filter(|d| paths.contains(&d.settings_path))
+        .collect();
+    if disabled.is_empty() {
+        return Ok(());
+    }
+
+    println!("Disabled hooks (restore with `claco hooks enable <id>`):");
+    for parked in &disabled {
+        if let Some(id) = parked.id() {
+            println!("  {id}  {}", format_hook_for(&id, &parked.hook));
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+pub fn handle_hooks_move(id: &HookId, before: &HookId) -> Result<()> {
+    if id.scope != before.scope || id.event != before.event || id.matcher != before.matcher {
+        anyhow::bail!("hooks can only be reordered within the same scope, event and matcher");
+    }
+    if id == before {
+        println!("Hook {id} is already in place");
+        return Ok(());
+    }
+
+    let settings_path = id.scope.settings_path()?;
+    let mut settings = load_settings(&settings_path)?;
+    let hooks = settings
+        .hooks
+        .as_mut()
+        .ok_or_else(|| anyhow::anyhow!("hook not found: {id}"))?;
+
+    let matchers = hooks.get(&before.event).map(Vec::as_slice).unwrap_or(&[]);
+    if locate_hook(matchers, &before.matcher, before.index).is_none() {
+        anyhow::bail!("hook not found: {before}");
+    }
+
+    let hook = remove_hook(hooks, id).ok_or_else(|| anyhow::anyhow!("hook not found: {id}"))?;
+    let display = format_hook_for(id, &hook);
+
+    // Removing the hook shifts every later hook down by one
+    let target = if before.index > id.index {
+        before.index - 1
+    } else {
+        before.index
+    };
+    insert_hook(hooks, &id.event, &id.matcher, target, hook);
+    save_settings_backed_up(&settings_path, &settings)?;
+
+    println!("Moved hook {id} before {before}: {display}");
+
+    Ok(())
+}
+
+/// Format a hook addressed by ID for display on a single line
+fn format_hook_for(id: &HookId, hook: &Hook) -> String {
+    format_hook(
+        &HookMatcher {
+            matcher: id.matcher.clone(),
+            hooks: Vec::new(),
+            other: Default::default(),
+        },
+        hook,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_remove_and_insert_hook() {
+        let mut hooks: Hooks = serde_json::from_value(json!({
+            "PreToolUse": [
+                {"matcher": "Bash", "hooks": [{"type": "command", "command": "a"}]},
+                {"matcher": "Edit", "hooks": [{"type": "command", "command": "b"}]},
+                {"matcher": "Bash", "hooks": [
+                    {"type": "command", "command": "c"},
+                    {"type": "command", "command": "d"}
+                ]}
+            ]
+        }))
+        .unwrap();
+        let id = |s: &str| s.parse::<HookId>().unwrap();
+        let commands = |hooks: &Hooks, matcher: &str| -> Vec<String> {
+            hooks[&HookEvent::PreToolUse]
+                .iter()
+                .filter(|m| m.matcher == matcher)
+                .flat_map(|m| m.hooks.iter().map(|h| h.command.clone()))
+                .collect()
+        };
+
+        // Indices count across every entry with the same matcher
+        let hook = remove_hook(&mut hooks, &id("user/PreToolUse/Bash/2")).unwrap();
+        assert_eq!(hook.command, "d");
+        assert!(remove_hook(&mut hooks, &id("user/PreToolUse/Bash/2")).is_none());
+
+        insert_hook(&mut hooks, &HookEvent::PreToolUse, "Bash", 0, hook);
+        assert_eq!(commands(&hooks, "Bash"), ["d", "a", "c"]);
+
+        // Empty matchers and events are dropped
+        let hook = remove_hook(&mut hooks, &id("user/PreToolUse/Edit/0")).unwrap();
+        assert_eq!(hooks[&HookEvent::PreToolUse].len(), 2);
+        insert_hook(&mut hooks, &HookEvent::PreToolUse, "Edit", 5, hook);
+        assert_eq!(commands(&hooks, "Edit"), ["b"]);
+
+        insert_hook(&mut hooks, &HookEvent::Stop, "", 0, Hook::new_command("e"));
+        remove_hook(&mut hooks, &id("user/Stop//0")).unwrap();
+        assert!(!hooks.contains_key(&HookEvent::Stop));
+
+        // An entry with fields besides its hooks is kept, and re-adding a hook restores them
+        let mut hooks: Hooks = serde_json::from_value(json!({
+            "Stop": [{"description": "notify", "hooks": [{"type": "command", "command": "f"}]}]
+        }))
+        .unwrap();
+        let hook = remove_hook(&mut hooks, &id("user/Stop//0")).unwrap();
+        insert_hook(&mut hooks, &HookEvent::Stop, "", 0, hook);
+        assert_eq!(
+            serde_json::to_value(&hooks).unwrap(),
+            json!({"Stop": [{"matcher": "", "description": "notify", "hooks": [{"type": "command", "command": "f"}]}]})
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::{Context, Result};
+use claco::hook_payload::HookPayload;
+use claco::Config;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::hook_runner::{run_hook_command, HookRun, DEFAULT_HOOK_TIMEOUT_SECS};
+
+/// One hook invocation recorded by `hook-exec`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookLogRecord {
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<String>,
+    pub command: String,
+    pub cwd: PathBuf,
+    pub duration_ms: u64,
+    /// Exit code, or `None` if the command was killed
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub timed_out: bool,
+    /// Decision the hook returned, from its JSON output or exit code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decision: Option<String>,
+}
+
+impl HookLogRecord {
+    /// Whether the hook failed or timed out
+    pub fn failed(&self) -> bool {
+        self.timed_out || self.exit_code != Some(0)
+    }
+
+    /// Whether the hook blocked or denied the action
+    pub fn blocked(&self) -> bool {
+        matches!(self.decision.as_deref(), Some("block" | "deny" | "stop"))
+    }
+}
+
+/// JSON lines audit log of hook invocations in claco's data directory
+pub fn hook_log_path() -> Result<PathBuf> {
+    Ok(Config::load()?.data_dir.join("hook-log.jsonl"))
+}
+
+/// Read every record from the audit log, skipping lines that fail to parse
+pub fn read_hook_log() -> Result<Vec<HookLogRecord>> {
+    let path = hook_log_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = fs::File::open(&path)
+        .with_context(|| format!("Failed to open hook log: {}", path.display()))?;
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(e) => eprintln!("warning: skipping invalid hook log line: {e}"),
+        }
+    }
+    Ok(records)
+}
+
+fn append_hook_log(record: &HookLogRecord) -> Result<()> {
+    let path = hook_log_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Decision a hook communicated, if any
+///
+/// JSON output takes precedence; otherwise exit code 2 means the action was
+/// blocked.
+fn hook_decision(run: &HookRun) -> Option<String> {
+    run.response()
+        .and_then(|response| response.decision_label())
+        .or((run.exit_code == Some(2)).then_some("block"))
+        .map(String::from)
+}
+
+/// Quote a word for `sh` unless it only has safe characters
+pub fn shell_quote(word: &str) -> String {
+    let safe = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c));
+    if safe {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+/// Run a hook command on behalf of Claude Code and record the invocation
+///
+/// Intended as the hook `command`: `claco hook-exec -- <real command>`. The
+/// payload on stdin is passed through unchanged, the real command's output
+/// and exit code are relayed back, and a record is appended to the audit log.
+pub fn handle_hook_exec(
+    command: Vec<String>,
+    matcher: Option<String>,
+    timeout: Option<u64>,
+) -> Result<()> {
+    // A single argument is a shell snippet; several are an argv to quote
+    let command = match command.as_slice() {
+        [single] => single.clone(),
+        words => words
+            .iter()
+            .map(|word| shell_quote(word))
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+
+    let mut input = Vec::new();
+    io::stdin().read_to_end(&mut input)?;
+    // The command still runs when the payload is not one claco understands
+    let payload: Option<HookPayload> = serde_json::from_slice(&input).ok();
+
+    let project_dir = std::env::var_os("CLAUDE_PROJECT_DIR")
+        .map(PathBuf::from)
+        .or_else(|| payload.as_ref().map(|p| PathBuf::from(&p.common().cwd)))
+        .map_or_else(std::env::current_dir, Ok)?;
+
+    let timeout = Duration::from_secs(timeout.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS));
+    // Claude Code already set up the environment; pass it through untouched
+    let run = run_hook_command(&command, &input, &project_dir, &BTreeMap::new(), timeout)?;
+
+    io::stdout().write_all(run.stdout.as_bytes())?;
+    io::stderr().write_all(run.stderr.as_bytes())?;
+    if run.timed_out {
+        eprintln!(
+            "hook-exec: command timed out after {}s: {command}",
+            timeout.as_secs()
+        );
+    }
+
+    let record = HookLogRecord {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        session_id: payload.as_ref().map(|p| p.common().session_id.clone()),
+        event: payload.as_ref().map(|p| p.event().to_string()),
+        tool: payload
+            .as_ref()
+            .and_then(|p| p.tool_name())
+            .map(String::from),
+        matcher,
+        command,
+        cwd: project_dir,
+        duration_ms: run.duration.as_millis() as u64,
+        exit_code: run.exit_code,
+        timed_out: run.timed_out,
+        decision: hook_decision(&run),
+    };
+
+    // Logging must never change what the hook tells Claude Code
+    if let Err(e) = append_hook_log(&record) {
+        eprintln!("warning: failed to write hook log: {e}");
+    }
+
+    io::stdout().flush()?;
+    // Timeouts and signals are reported as non-blocking errors
+    std::process::exit(run.exit_code.unwrap_or(1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(exit_code: i32, stdout: &str) -> HookRun {
+        HookRun {
+            exit_code: Some(exit_code),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            duration: Duration::ZERO,
+            timed_out: false,
+        }
+    }
+
+    #[test]
+    fn test_hook_decision() {
+        assert_eq!(hook_decision(&run(0, "")), None);
+        assert_eq!(hook_decision(&run(2, "")).as_deref(), Some("block"));
+        assert_eq!(
+            hook_decision(&run(0, r#"{"decision": "approve"}"#)).as_deref(),
+            Some("approve")
+        );
+        assert_eq!(
+            hook_decision(&run(
+                0,
+                r#"{"hookSpecificOutput": {"hookEventName": "PreToolUse", "permissionDecision": "deny"}}"#
+            ))
+            .as_deref(),
+            Some("deny")
+        );
+        assert_eq!(
+            hook_decision(&run(0, r#"{"continue": false}"#)).as_deref(),
+            Some("stop")
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("./fmt.sh"), "./fmt.sh");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
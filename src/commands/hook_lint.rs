@@ -0,0 +1,408 @@
+use claco::{claude_home, HookEvent};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Tools built into Claude Code that PreToolUse and PostToolUse matchers can name
+pub const BUILTIN_TOOLS: &[&str] = &[
+    "Agent",
+    "Bash",
+    "BashOutput",
+    "Edit",
+    "ExitPlanMode",
+    "Glob",
+    "Grep",
+    "KillShell",
+    "LS",
+    "MultiEdit",
+    "NotebookEdit",
+    "NotebookRead",
+    "Read",
+    "SlashCommand",
+    "Task",
+    "TodoWrite",
+    "WebFetch",
+    "WebSearch",
+    "Write",
+];
+
+/// Shell builtins a hook command may start with that are not on PATH
+const SHELL_BUILTINS: &[&str] = &[
+    ".", ":", "[", "cd", "command", "echo", "eval", "exec", "exit", "export", "false", "printf",
+    "read", "set", "source", "test", "true",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LintIssue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl LintIssue {
+    fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+}
+
+/// Values a matcher is compared against for an event
+fn matcher_candidates(event: &HookEvent) -> &'static [&'static str] {
+    match event {
+        HookEvent::PreToolUse | HookEvent::PostToolUse => BUILTIN_TOOLS,
+        HookEvent::PreCompact => &["manual", "auto"],
+        HookEvent::SessionStart => &["startup", "resume", "clear", "compact"],
+        _ => &[],
+    }
+}
+
+/// Check that a matcher compiles and can match something for its event
+///
+/// MCP tools are named `mcp__<server>__<tool>`, so a matcher naming MCP tools
+/// is accepted when the servers it mentions are configured.
+pub fn lint_matcher(
+    event: &HookEvent,
+    matcher: &str,
+    mcp_servers: &BTreeSet<String>,
+) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let pattern = matcher.trim();
+
+    if !event.is_known() {
+        issues.push(LintIssue::warning(format!(
+            "unknown event '{event}' is ignored by Claude Code"
+        )));
+        return issues;
+    }
+
+    if !event.uses_matcher() {
+        if !pattern.is_empty() {
+            issues.push(LintIssue::warning(format!(
+                "{event} does not use matchers, so '{pattern}' is ignored"
+            )));
+        }
+        return issues;
+    }
+
+    if pattern.is_empty() {
+        return issues;
+    }
+
+    if pattern == "*" {
+        issues.push(LintIssue::warning(
+            "matcher '*' matches everything - list the intended values or leave the matcher empty",
+        ));
+        return issues;
+    }
+
+    let re = match regex::Regex::new(&format!("^(?:{pattern})$")) {
+        Ok(re) => re,
+        Err(err) => {
+            let reason = err.to_string();
+            let reason = reason.lines().last().unwrap_or_default().trim();
+            let reason = reason.trim_start_matches("error: ");
+            issues.push(LintIssue::error(format!(
+                "matcher '{pattern}' is not a valid regex: {reason}"
+            )));
+            return issues;
+        }
+    };
+
+    let candidates = matcher_candidates(event);
+    if candidates.iter().any(|candidate| re.is_match(candidate)) {
+        return issues;
+    }
+
+    let is_tool_event = matches!(event, HookEvent::PreToolUse | HookEvent::PostToolUse);
+    if is_tool_event && pattern.contains("mcp__") {
+        let server_re = regex::Regex::new(r"mcp__([A-Za-z0-9_-]+?)__").unwrap();
+        let servers: Vec<&str> = server_re
+            .captures_iter(pattern)
+            .filter_map(|captures| captures.get(1))
+            .map(|m| m.as_str())
+            .collect();
+
+        if mcp_servers.is_empty() {
+            issues.push(LintIssue::warning(format!(
+                "matcher '{pattern}' names MCP tools but no MCP servers are configured"
+            )));
+        }
+        for server in servers {
+            if !mcp_servers.is_empty() && !mcp_servers.contains(server) {
+                issues.push(LintIssue::warning(format!(
+                    "matcher '{pattern}' names MCP server '{server}', which is not configured"
+                )));
+            }
+        }
+        return issues;
+    }
+
+    // Matchers are case-sensitive, which is an easy mistake to make
+    let suggestion = regex::Regex::new(&format!("(?i)^(?:{pattern})$"))
+        .ok()
+        .and_then(|re| candidates.iter().find(|candidate| re.is_match(candidate)));
+    let message = match suggestion {
+        Some(candidate) => format!(
+            "matcher '{pattern}' matches nothing (matchers are case-sensitive - did you mean '{candidate}'?)"
+        ),
+        None if is_tool_event => {
+            format!("matcher '{pattern}' matches no built-in tool or configured MCP tool")
+        }
+        None => format!(
+            "matcher '{pattern}' matches none of: {}",
+            candidates.join(", ")
+        ),
+    };
+    issues.push(LintIssue::warning(message));
+
+    issues
+}
+
+/// First word of a shell command with quotes removed
+///
+/// Leading `NAME=value` environment assignments are skipped.
+pub fn command_program(command: &str) -> Option<String> {
+    let mut chars = command.trim_start().chars().peekable();
+
+    loop {
+        let mut word = String::new();
+        let mut quote = None;
+        while let Some(&c) = chars.peek() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => word.push(c),
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None if c.is_whitespace() => break,
+                None => word.push(c),
+            }
+            chars.next();
+        }
+
+        if word.is_empty() {
+            return None;
+        }
+
+        let is_assignment = word
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && !name.contains('/'));
+        if !is_assignment {
+            return Some(word);
+        }
+
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+    }
+}
+
+/// Check that a hook command starts with an executable or an existing script
+pub fn lint_command(command: &str, project_dir: &Path) -> Vec<LintIssue> {
+    let program = match command_program(command) {
+        Some(program) => program,
+        None => return vec![LintIssue::error("command is empty")],
+    };
+
+    let home = dirs::home_dir().unwrap_or_default();
+    let project = project_dir.to_string_lossy();
+    let mut expanded = program
+        .replace("${CLAUDE_PROJECT_DIR}", &project)
+        .replace("$CLAUDE_PROJECT_DIR", &project)
+        .replace("${HOME}", &home.to_string_lossy())
+        .replace("$HOME", &home.to_string_lossy());
+    if let Some(rest) = expanded.strip_prefix("~/") {
+        expanded = home.join(rest).to_string_lossy().to_string();
+    }
+
+    if expanded.contains('$') {
+        return vec![LintIssue::warning(format!(
+            "cannot resolve '{program}' - it uses environment variables claco does not know"
+        ))];
+    }
+
+    if expanded.contains('/') {
+        let path = project_dir.join(&expanded);
+        if !path.is_file() {
+            return vec![LintIssue::error(format!(
+                "script not found: {}",
+                path.display()
+            ))];
+        }
+        if !is_executable(&path) {
+            return vec![LintIssue::warning(format!(
+                "script is not executable: {}",
+                path.display()
+            ))];
+        }
+        return Vec::new();
+    }
+
+    if SHELL_BUILTINS.contains(&expanded.as_str()) || find_in_path(&expanded).is_some() {
+        return Vec::new();
+    }
+
+    vec![LintIssue::error(format!("'{expanded}' not found in PATH"))]
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file() && is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Names of MCP servers configured for a project
+///
+/// Reads `.mcp.json` in the project and both the global and per-project
+/// `mcpServers` in `~/.claude.json`. Unreadable files are skipped.
+pub fn configured_mcp_servers(project_dir: &Path) -> BTreeSet<String> {
+    let mut servers = BTreeSet::new();
+    let mut add_servers = |value: Option<&Value>| {
+        if let Some(map) = value.and_then(|v| v.as_object()) {
+            servers.extend(map.keys().cloned());
+        }
+    };
+
+    let read_json = |path: PathBuf| -> Option<Value> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    };
+
+    if let Some(mcp) = read_json(project_dir.join(".mcp.json")) {
+        add_servers(mcp.get("mcpServers"));
+    }
+
+    let claude_json = claude_home()
+        .ok()
+        .and_then(|dir| dir.parent().map(|home| home.join(".claude.json")));
+    if let Some(config) = claude_json.and_then(read_json) {
+        add_servers(config.get("mcpServers"));
+        add_servers(
+            config
+                .get("projects")
+                .and_then(|projects| projects.get(project_dir.to_string_lossy().as_ref()))
+                .and_then(|project| project.get("mcpServers")),
+        );
+    }
+
+    servers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn severities(issues: &[LintIssue]) -> Vec<Severity> {
+        issues.iter().map(|issue| issue.severity).collect()
+    }
+
+    #[test]
+    fn test_lint_matcher() {
+        let none = BTreeSet::new();
+        let github = BTreeSet::from(["github".to_string()]);
+        let pre = HookEvent::PreToolUse;
+
+        assert!(lint_matcher(&pre, "", &none).is_empty());
+        assert!(lint_matcher(&pre, "Edit|Write", &none).is_empty());
+        assert!(lint_matcher(&pre, "Notebook.*", &none).is_empty());
+        assert!(lint_matcher(&pre, "mcp__github__.*", &github).is_empty());
+
+        assert_eq!(
+            severities(&lint_matcher(&pre, "*", &none)),
+            [Severity::Warning]
+        );
+        assert_eq!(
+            severities(&lint_matcher(&pre, "Edit(", &none)),
+            [Severity::Error]
+        );
+        assert!(lint_matcher(&pre, "bash", &none)[0]
+            .message
+            .contains("did you mean 'Bash'"));
+        assert_eq!(
+            severities(&lint_matcher(&pre, "mcp__slack__post", &github)),
+            [Severity::Warning]
+        );
+
+        assert!(lint_matcher(&HookEvent::SessionStart, "resume", &none).is_empty());
+        assert_eq!(lint_matcher(&HookEvent::PreCompact, "Bash", &none).len(), 1);
+        assert_eq!(lint_matcher(&HookEvent::Stop, "Bash", &none).len(), 1);
+    }
+
+    #[test]
+    fn test_command_program() {
+        assert_eq!(command_program("jq -r .x").as_deref(), Some("jq"));
+        assert_eq!(
+            command_program("\"$CLAUDE_PROJECT_DIR\"/.claude/hooks/fmt.sh --all").as_deref(),
+            Some("$CLAUDE_PROJECT_DIR/.claude/hooks/fmt.sh")
+        );
+        assert_eq!(
+            command_program("FOO=1 BAR='a b' ./run.sh").as_deref(),
+            Some("./run.sh")
+        );
+        assert_eq!(command_program("   "), None);
+    }
+
+    #[test]
+    fn test_lint_command() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(lint_command("sh -c 'exit 0'", dir.path()).is_empty());
+        assert!(lint_command("echo hi", dir.path()).is_empty());
+        assert_eq!(
+            severities(&lint_command("definitely-not-a-real-binary", dir.path())),
+            [Severity::Error]
+        );
+        assert_eq!(
+            severities(&lint_command(
+                "\"$CLAUDE_PROJECT_DIR\"/hooks/missing.sh",
+                dir.path()
+            )),
+            [Severity::Error]
+        );
+
+        // Scripts that exist but lack the executable bit only warn
+        #[cfg(unix)]
+        {
+            let script = dir.path().join("hook.sh");
+            fs::write(&script, "#!/bin/sh\n").unwrap();
+            assert_eq!(
+                severities(&lint_command("\"$CLAUDE_PROJECT_DIR\"/hook.sh", dir.path())),
+                [Severity::Warning]
+            );
+        }
+    }
+}
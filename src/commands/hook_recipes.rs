@@ -0,0 +1,332 @@
+use anyhow::Result;
+use claco::HookEvent;
+use std::collections::HashMap;
+
+use super::hook_exec::shell_quote;
+
+/// Characters a command parameter may not contain, as they would change what the script runs
+const SHELL_METACHARACTERS: &str = ";&|<>()$`\\\"'*?[]{}~#!\n\r";
+
+/// How a parameter value is written into the helper script
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamKind {
+    /// A single string, quoted as one shell word
+    Text,
+    /// Whitespace-separated items, each quoted as its own shell word
+    Words,
+    /// A command line inserted as is, so shell metacharacters are rejected
+    Command,
+}
+
+/// A parameter that customizes a recipe's helper script
+pub struct RecipeParam {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default: &'static str,
+    pub kind: ParamKind,
+}
+
+impl RecipeParam {
+    /// The value as shell source for the script
+    fn render(&self, value: &str) -> Result<String> {
+        match self.kind {
+            ParamKind::Text => Ok(shell_quote(value)),
+            ParamKind::Words => Ok(value
+                .split_whitespace()
+                .map(shell_quote)
+                .collect::<Vec<_>>()
+                .join(" ")),
+            ParamKind::Command => {
+                if let Some(c) = value.chars().find(|c| SHELL_METACHARACTERS.contains(*c)) {
+                    anyhow::bail!(
+                        "parameter '{}' must be a plain command line, found {c:?} - \
+                         put anything more complex in a script and pass its path",
+                        self.name
+                    );
+                }
+                if value.trim().is_empty() {
+                    anyhow::bail!("parameter '{}' must not be empty", self.name);
+                }
+                Ok(value.trim().to_string())
+            }
+        }
+    }
+}
+
+/// A curated hook setup that `hooks install` can add to settings
+///
+/// Each recipe installs one hook. When `script` is set, it is rendered with
+/// the recipe parameters and written to `.claude/hooks/claco-<name>.sh`, and
+/// the hook runs that script; otherwise the hook runs `command` directly.
+pub struct Recipe {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub event: HookEvent,
+    pub matcher: &'static str,
+    pub script: Option<&'static str>,
+    pub command: Option<&'static str>,
+    pub timeout: Option<u64>,
+    pub params: &'static [RecipeParam],
+}
+
+impl Recipe {
+    /// File name of the helper script written for this recipe
+    pub fn script_name(&self) -> String {
+        format!("claco-{}.sh", self.name)
+    }
+
+    /// Render the helper script, filling `{{param}}` placeholders
+    ///
+    /// Parameters not given in `values` use their defaults; unknown names are
+    /// rejected so typos don't go unnoticed. Values are quoted or checked
+    /// according to their [`ParamKind`], so they can't inject shell code.
+    pub fn render_script(&self, values: &HashMap<String, String>) -> Result<Option<String>> {
+        for name in values.keys() {
+            if !self.params.iter().any(|param| param.name == name) {
+                let valid: Vec<&str> = self.params.iter().map(|param| param.name).collect();
+                if valid.is_empty() {
+                    anyhow::bail!("recipe '{}' takes no parameters", self.name);
+                }
+                anyhow::bail!(
+                    "unknown parameter '{name}' for recipe '{}' - valid parameters are: {}",
+                    self.name,
+                    valid.join(", ")
+                );
+            }
+        }
+
+        let Some(script) = self.script else {
+            return Ok(None);
+        };
+        let mut rendered = script.to_string();
+        for param in self.params {
+            let value = values
+                .get(param.name)
+                .map(String::as_str)
+                .unwrap_or(param.default);
+            rendered = rendered.replace(&format!("{{{{{}}}}}", param.name), &param.render(value)?);
+        }
+        Ok(Some(rendered))
+    }
+}
+
+const FORMAT_SCRIPT: &str = r#"#!/bin/sh
+# Installed by `claco hooks install format`
+# Formats files after Claude edits them, picking the formatter by extension.
+file_path=$(jq -r '.tool_input.file_path // empty')
+[ -n "$file_path" ] && [ -f "$file_path" ] || exit 0
+
+case "$file_path" in
+  *.rs) {{rust}} "$file_path" ;;
+  *.py) {{python}} "$file_path" ;;
+  *.go) {{go}} "$file_path" ;;
+  *.js|*.jsx|*.ts|*.tsx|*.json|*.css|*.md) {{web}} "$file_path" ;;
+esac
+
+# A failing formatter should never block Claude
+exit 0
+"#;
+
+const PROTECT_SCRIPT: &str = r#"#!/bin/sh
+# Installed by `claco hooks install protect`
+# Blocks edits to protected paths; exit code 2 feeds the reason back to Claude.
+set -f
+file_path=$(jq -r '.tool_input.file_path // .tool_input.notebook_path // empty')
+[ -n "$file_path" ] || exit 0
+
+for pattern in {{paths}}; do
+  case "$file_path" in
+    *"$pattern"*)
+      echo "Blocked: $file_path is protected (matches '$pattern')" >&2
+      exit 2
+      ;;
+  esac
+done
+exit 0
+"#;
+
+const NOTIFY_SCRIPT: &str = r#"#!/bin/sh
+# Installed by `claco hooks install notify`
+# Sends a desktop notification when Claude finishes, falling back to a terminal bell.
+message={{message}}
+
+if command -v osascript >/dev/null 2>&1; then
+  osascript -e "display notification \"$message\" with title \"Claude Code\""
+elif command -v notify-send >/dev/null 2>&1; then
+  notify-send "Claude Code" "$message"
+else
+  printf '\a' > /dev/tty 2>/dev/null
+fi
+exit 0
+"#;
+
+const LOG_PROMPTS_SCRIPT: &str = r#"#!/bin/sh
+# Installed by `claco hooks install log-prompts`
+# Appends every submitted prompt to a JSON lines log in the project.
+log_file="${CLAUDE_PROJECT_DIR:-.}"/{{file}}
+mkdir -p "$(dirname "$log_file")"
+jq -c '{time: (now | todate), session_id, prompt}' >> "$log_file"
+exit 0
+"#;
+
+/// Recipes available to `hooks install`
+pub const RECIPES: &[Recipe] = &[
+    Recipe {
+        name: "format",
+        description: "Run a formatter on files after Edit/Write, chosen by extension (needs jq)",
+        event: HookEvent::PostToolUse,
+        matcher: "Edit|MultiEdit|Write",
+        script: Some(FORMAT_SCRIPT),
+        command: None,
+        timeout: Some(30),
+        params: &[
+            RecipeParam {
+                name: "rust",
+                description: "Formatter for .rs files",
+                default: "rustfmt --edition 2021",
+                kind: ParamKind::Command,
+            },
+            RecipeParam {
+                name: "python",
+                description: "Formatter for .py files",
+                default: "black -q",
+                kind: ParamKind::Command,
+            },
+            RecipeParam {
+                name: "go",
+                description: "Formatter for .go files",
+                default: "gofmt -w",
+                kind: ParamKind::Command,
+            },
+            RecipeParam {
+                name: "web",
+                description: "Formatter for JS/TS/JSON/CSS/Markdown files",
+                default: "npx --no-install prettier --write",
+                kind: ParamKind::Command,
+            },
+        ],
+    },
+    Recipe {
+        name: "protect",
+        description: "Block edits to protected paths such as .env and .git/ (needs jq)",
+        event: HookEvent::PreToolUse,
+        matcher: "Edit|MultiEdit|Write|NotebookEdit",
+        script: Some(PROTECT_SCRIPT),
+        command: None,
+        timeout: None,
+        params: &[RecipeParam {
+            name: "paths",
+            description: "Space-separated path fragments to protect",
+            default: ".env .git/ secrets/",
+            kind: ParamKind::Words,
+        }],
+    },
+    Recipe {
+        name: "notify",
+        description: "Desktop notification (or terminal bell) when Claude stops",
+        event: HookEvent::Stop,
+        matcher: "",
+        script: Some(NOTIFY_SCRIPT),
+        command: None,
+        timeout: None,
+        params: &[RecipeParam {
+            name: "message",
+            description: "Notification text",
+            default: "Claude has finished",
+            kind: ParamKind::Text,
+        }],
+    },
+    Recipe {
+        name: "log-prompts",
+        description: "Append submitted prompts to a JSON lines log (needs jq)",
+        event: HookEvent::UserPromptSubmit,
+        matcher: "",
+        script: Some(LOG_PROMPTS_SCRIPT),
+        command: None,
+        timeout: None,
+        params: &[RecipeParam {
+            name: "file",
+            description: "Log file, relative to the project directory",
+            default: ".claude/prompts.log",
+            kind: ParamKind::Text,
+        }],
+    },
+    Recipe {
+        name: "guard",
+        description: "Check Bash commands and file edits against a guard policy (`claco guard`)",
+        event: HookEvent::PreToolUse,
+        matcher: "Bash|Edit|MultiEdit|Write|NotebookEdit",
+        script: None,
+        command: Some("claco guard"),
+        timeout: Some(10),
+        params: &[],
+    },
+];
+
+/// Look up a recipe by name
+pub fn find_recipe(name: &str) -> Option<&'static Recipe> {
+    RECIPES.iter().find(|recipe| recipe.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_recipe_scripts() {
+        for recipe in RECIPES {
+            let script = recipe.render_script(&HashMap::new()).unwrap();
+            if let Some(script) = script {
+                assert!(!script.contains("{{"), "{} left a placeholder", recipe.name);
+            }
+        }
+
+        let recipe = find_recipe("protect").unwrap();
+        let values = HashMap::from([("paths".to_string(), "config/prod".to_string())]);
+        let script = recipe.render_script(&values).unwrap().unwrap();
+        assert!(script.contains("for pattern in config/prod; do"));
+
+        let values = HashMap::from([("path".to_string(), "x".to_string())]);
+        assert!(recipe.render_script(&values).is_err());
+    }
+
+    #[test]
+    fn test_recipe_params_are_quoted() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let marker = temp.path().join("pwned");
+        let message = format!(r#"it's "done" $(touch {}) `id`"#, marker.display());
+
+        let recipe = find_recipe("notify").unwrap();
+        let values = HashMap::from([("message".to_string(), message.clone())]);
+        let script = recipe.render_script(&values).unwrap().unwrap();
+        let assignment = script
+            .lines()
+            .find(|line| line.starts_with("message="))
+            .unwrap();
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!(r#"{assignment}; printf %s "$message""#))
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), message);
+        assert!(!marker.exists());
+
+        let recipe = find_recipe("protect").unwrap();
+        let values = HashMap::from([("paths".to_string(), "a'b $(x)".to_string())]);
+        let script = recipe.render_script(&values).unwrap().unwrap();
+        assert!(script.contains(r#"for pattern in 'a'\''b' '$(x)'; do"#));
+
+        // Command lines can't be quoted as a whole, so metacharacters are refused
+        let recipe = find_recipe("format").unwrap();
+        let values = HashMap::from([("rust".to_string(), "rustfmt --check".to_string())]);
+        let script = recipe.render_script(&values).unwrap().unwrap();
+        assert!(script.contains(r#"*.rs) rustfmt --check "$file_path" ;;"#));
+        for value in ["rustfmt; rm -rf ~", "rustfmt $(id)", "rustfmt \"x\"", ""] {
+            let values = HashMap::from([("rust".to_string(), value.to_string())]);
+            assert!(
+                recipe.render_script(&values).is_err(),
+                "{value:?} was accepted"
+            );
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use anyhow::{Context, Result};
+use claco::{
+    load_settings, save_settings, save_settings_backed_up, write_settings_file, Hook, HookEvent,
+    Scope, Settings,
+};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::hook_edit::insert_hook;
+use super::hook_exec::shell_quote;
+
+/// Install a temporary hook that saves every payload for `event` to `out`
+///
+/// The hook is removed again once recording stops with Ctrl-C, SIGTERM or
+/// SIGHUP, or on any earlier exit; see [`RecordingHook::remove`].
+pub async fn handle_hooks_record(
+    event: HookEvent,
+    out: PathBuf,
+    matcher: String,
+    scope: Scope,
+) -> Result<()> {
+    if !matcher.is_empty() && !event.uses_matcher() {
+        eprintln!("error: event '{event}' does not use matchers - omit --matcher");
+        return Ok(());
+    }
+
+    fs::create_dir_all(&out)
+        .with_context(|| format!("Failed to create output directory: {}", out.display()))?;
+    // The hook runs from Claude's working directory, so use absolute paths
+    let out = fs::canonicalize(&out)?;
+    let exe = std::env::current_exe().context("Failed to locate the claco executable")?;
+    let command = format!(
+        "{} hooks capture --out {}",
+        shell_quote(&exe.to_string_lossy()),
+        shell_quote(&out.to_string_lossy())
+    );
+
+    let settings_path = scope.settings_path()?;
+    offer_leftover_removal(&settings_path)?;
+    let original = match fs::read(&settings_path) {
+        Ok(original) => Some(original),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", settings_path.display()))
+        }
+    };
+
+    let mut settings = load_settings(&settings_path)?;
+    let hooks = settings.hooks.get_or_insert_with(Default::default);
+    insert_hook(
+        hooks,
+        &event,
+        &matcher,
+        usize::MAX,
+        Hook::new_command(command.clone()),
+    );
+    save_settings(&settings_path, &settings)?;
+    // From here on the hook is removed again however this function exits
+    let mut recording = RecordingHook {
+        installed: fs::read(&settings_path).ok(),
+        settings_path,
+        original,
+        event,
+        command,
+        removed: false,
+    };
+
+    let first = next_fixture_number(&out)?;
+    println!(
+        "Recording {} payloads to {}",
+        recording.event,
+        out.display()
+    );
+    println!(
+        "Recording hook added to {}",
+        recording.settings_path.display()
+    );
+    println!(
+        "Start a new Claude Code session in this project, then press Ctrl-C here to stop recording."
+    );
+
+    let stopped = wait_for_stop().await;
+
+    // Put the settings back before anything else can fail
+    recording.remove()?;
+    stopped.context("Failed to wait for a stop signal")?;
+
+    let recorded = next_fixture_number(&out)? - first;
+    println!();
+    println!(
+        "Removed the recording hook from {}",
+        recording.settings_path.display()
+    );
+    println!("Recorded {recorded} payload(s) in {}", out.display());
+
+    Ok(())
+}
+
+/// Wait for Ctrl-C, or for the terminal or a process manager to end claco
+async fn wait_for_stop() -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut hangup = signal(SignalKind::hangup())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = terminate.recv() => Ok(()),
+            _ = hangup.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await
+}
+
+/// Whether `command` is a hook installed by `hooks record`
+fn is_capture_command(command: &str) -> bool {
+    command.contains(" hooks capture --out ")
+}
+
+/// Remove the hooks for which `remove` returns true, dropping emptied matchers and events
+fn remove_hooks(settings: &mut Settings, remove: impl Fn(&HookEvent, &Hook) -> bool) {
+    let Some(hooks) = settings.hooks.as_mut() else {
+        return;
+    };
+    for (event, matchers) in hooks.iter_mut() {
+        for matcher in matchers.iter_mut() {
+            matcher.hooks.retain(|hook| !remove(event, hook));
+        }
+        matchers.retain(|matcher| !matcher.hooks.is_empty());
+    }
+    hooks.retain(|_, matchers| !matchers.is_empty());
+    if hooks.is_empty() {
+        settings.hooks = None;
+    }
+}
+
+/// Offer to remove capture hooks left behind by a recording that was killed
+fn offer_leftover_removal(settings_path: &PathBuf) -> Result<()> {
+    let mut settings = load_settings(settings_path)?;
+    let leftovers: Vec<(HookEvent, String)> = settings
+        .hooks
+        .iter()
+        .flatten()
+        .flat_map(|(event, matchers)| {
+            matchers
+                .iter()
+                .flat_map(|matcher| &matcher.hooks)
+                .filter(|hook| is_capture_command(&hook.command))
+                .map(move |hook| (event.clone(), hook.command.clone()))
+        })
+        .collect();
+    if leftovers.is_empty() {
+        return Ok(());
+    }
+
+    println!(
+        "Found {} recording hook(s) left from an earlier `hooks record` in {}:",
+        leftovers.len(),
+        settings_path.display()
+    );
+    for (event, command) in &leftovers {
+        println!("  {event}: {command}");
+    }
+    print!("Remove them? (y/N): ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    if input != "y" && input != "yes" {
+        println!("Keeping them");
+        return Ok(());
+    }
+
+    remove_hooks(&mut settings, |_, hook| is_capture_command(&hook.command));
+    save_settings_backed_up(settings_path, &settings)?;
+    println!("Removed {} recording hook(s)", leftovers.len());
+    Ok(())
+}
+
+/// The temporary hook installed by `hooks record`
+struct RecordingHook {
+    settings_path: PathBuf,
+    /// Bytes of the settings file before recording, `None` if it did not exist
+    original: Option<Vec<u8>>,
+    /// Bytes written when installing the hook
+    installed: Option<Vec<u8>>,
+    event: HookEvent,
+    command: String,
+    removed: bool,
+}
+
+impl RecordingHook {
+    /// Take the hook out of the settings file again
+    ///
+    /// An untouched file gets its original bytes back (or is removed if it did
+    /// not exist). If it was edited while recording, only the hook is removed
+    /// so those edits are kept.
+    fn remove(&mut self) -> Result<()> {
+        self.removed = true;
+        let path = &self.settings_path;
+
+        let current = match fs::read(path) {
+            Ok(current) => current,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        if self.installed.as_ref() == Some(&current) {
+            return match &self.original {
+                Some(original) => write_settings_file(path, original),
+                None => fs::remove_file(path)
+                    .with_context(|| format!("Failed to remove {}", path.display())),
+            };
+        }
+
+        let mut settings = load_settings(path)?;
+        remove_hooks(&mut settings, |event, hook| {
+            *event == self.event && hook.command == self.command
+        });
+        save_settings(path, &settings)?;
+        eprintln!(
+            "warning: {} changed while recording; only the recording hook was removed",
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+impl Drop for RecordingHook {
+    fn drop(&mut self) {
+        if !self.removed {
+            if let Err(e) = self.remove() {
+                eprintln!(
+                    "error: failed to remove the recording hook from {}: {e:#}",
+                    self.settings_path.display()
+                );
+            }
+        }
+    }
+}
+
+/// Save the hook payload on stdin as the next numbered file in `out`
+///
+/// Valid JSON is pretty-printed so fixtures diff well; anything else is kept
+/// as-is. Nothing is printed on stdout, which Claude Code would interpret.
+pub fn handle_hooks_capture(out: &Path) -> Result<()> {
+    let mut input = Vec::new();
+    io::stdin().read_to_end(&mut input)?;
+
+    let content = match serde_json::from_slice::<Value>(&input) {
+        Ok(value) => format!("{}\n", serde_json::to_string_pretty(&value)?).into_bytes(),
+        Err(_) => input,
+    };
+
+    fs::create_dir_all(out)?;
+    write_fixture(out, &content)?;
+    Ok(())
+}
+
+/// Number the next fixture in `dir` gets: one past the highest `NNNN.json`
+fn next_fixture_number(dir: &Path) -> Result<u32> {
+    let mut highest = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            if let Some(number) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u32>().ok())
+            {
+                highest = highest.max(number);
+            }
+        }
+    }
+    Ok(highest + 1)
+}
+
+/// Write `content` to the next free `NNNN.json` in `dir`
+///
+/// Hooks for parallel tool calls run concurrently, so the file is created
+/// exclusively and the number bumped if another capture got there first.
+fn write_fixture(dir: &Path, content: &[u8]) -> Result<PathBuf> {
+    let mut number = next_fixture_number(dir)?;
+    loop {
+        let path = dir.join(format!("{number:04}.json"));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(content)?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => number += 1,
+            Err(e) => return Err(e).with_context(|| format!("Failed to write {}", path.display())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_fixture_numbering() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(next_fixture_number(dir.path()).unwrap(), 1);
+
+        let first = write_fixture(dir.path(), b"{}").unwrap();
+        let second = write_fixture(dir.path(), b"{}").unwrap();
+        assert_eq!(first.file_name().unwrap(), "0001.json");
+        assert_eq!(second.file_name().unwrap(), "0002.json");
+
+        // Unrelated files are ignored and gaps are never refilled
+        fs::write(dir.path().join("notes.json"), "{}").unwrap();
+        fs::write(dir.path().join("0007.json"), "{}").unwrap();
+        let next = write_fixture(dir.path(), b"{}").unwrap();
+        assert_eq!(next.file_name().unwrap(), "0008.json");
+    }
+
+    #[test]
+    fn test_recording_hook_removal() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings_path = dir.path().join("settings.json");
+        let original = b"{\"model\":  \"opus\"}".to_vec();
+        let recording = |installed: &[u8]| {
+            fs::write(&settings_path, installed).unwrap();
+            RecordingHook {
+                settings_path: settings_path.clone(),
+                original: Some(original.clone()),
+                installed: Some(installed.to_vec()),
+                event: HookEvent::Stop,
+                command: "claco hooks capture".to_string(),
+                removed: false,
+            }
+        };
+        let installed =
+            br#"{"model": "opus", "hooks": {"Stop": [{"hooks": [{"type": "command", "command": "claco hooks capture"}]}]}}"#;
+
+        // Untouched: the original bytes come back, even from a drop
+        drop(recording(installed));
+        assert_eq!(fs::read(&settings_path).unwrap(), original);
+
+        // Edited while recording: the edit stays, the hook goes
+        let mut edited = recording(installed);
+        fs::write(
+            &settings_path,
+            br#"{"model": "sonnet", "hooks": {"Stop": [{"hooks": [{"type": "command", "command": "claco hooks capture"}]}]}}"#,
+        )
+        .unwrap();
+        edited.remove().unwrap();
+        let settings = load_settings(&settings_path).unwrap();
+        assert_eq!(settings.other["model"], "sonnet");
+        assert!(settings.hooks.is_none());
+    }
+
+    #[test]
+    fn test_remove_leftover_capture_hooks() {
+        let mut settings: Settings = serde_json::from_value(serde_json::json!({"hooks": {
+            "Stop": [{"hooks": [{"type": "command", "command": "/bin/claco hooks capture --out /tmp/x"}]}],
+            "PreToolUse": [{"matcher": "Bash", "hooks": [
+                {"type": "command", "command": "claco guard"},
+                {"type": "command", "command": "'/opt/claco' hooks capture --out '/tmp/y z'"}
+            ]}]
+        }}))
+        .unwrap();
+        assert!(!is_capture_command("claco guard"));
+
+        remove_hooks(&mut settings, |_, hook| is_capture_command(&hook.command));
+        let hooks = settings.hooks.unwrap();
+        assert_eq!(hooks.len(), 1);
+        let matchers = &hooks[&HookEvent::PreToolUse];
+        assert_eq!(matchers[0].hooks.len(), 1);
+        assert_eq!(matchers[0].hooks[0].command, "claco guard");
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use claco::hook_payload::HookResponse;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default hook timeout used by Claude Code when a hook sets none
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 60;
+
+/// Outcome of running a hook command
+#[derive(Debug)]
+pub struct HookRun {
+    /// Exit code, or `None` if the process was killed
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration: Duration,
+    pub timed_out: bool,
+}
+
+impl HookRun {
+    /// Structured response printed on stdout, if the hook returned one
+    pub fn response(&self) -> Option<HookResponse> {
+        serde_json::from_str(self.stdout.trim()).ok()
+    }
+
+    /// Describe the exit code the way Claude Code interprets it
+    pub fn describe_exit(&self) -> String {
+        match self.exit_code {
+            _ if self.timed_out => "timed out (killed)".to_string(),
+            Some(0) => "0 (success)".to_string(),
+            Some(2) => "2 (blocking error: stderr is fed back to Claude)".to_string(),
+            Some(code) => format!("{code} (non-blocking error: stderr is shown to the user)"),
+            None => "terminated by signal".to_string(),
+        }
+    }
+}
+
+/// Run a hook command through the shell with the payload on stdin
+///
+/// The command inherits claco's environment plus `env`. Once `timeout` has
+/// elapsed the command is killed along with anything it started. The input
+/// is passed through byte for byte.
+pub fn run_hook_command(
+    command: &str,
+    input: &[u8],
+    project_dir: &Path,
+    env: &BTreeMap<String, String>,
+    timeout: Duration,
+) -> Result<HookRun> {
+    let input = input.to_vec();
+    let start = Instant::now();
+
+    let mut shell = Command::new("sh");
+    shell
+        .arg("-c")
+        .arg(command)
+        .current_dir(project_dir)
+        .envs(env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // A process group of its own, so a timeout also kills the commands the
+    // shell started, which would otherwise keep the output pipes open
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+    let mut child = shell
+        .spawn()
+        .with_context(|| format!("Failed to start hook command: {command}"))?;
+
+    // Feed stdin and drain the output pipes on separate threads so a chatty
+    // hook cannot block on a full pipe
+    let mut stdin = child.stdin.take();
+    let stdin_writer = thread::spawn(move || {
+        if let Some(ref mut stdin) = stdin {
+            // The hook may exit without reading its input
+            let _ = stdin.write_all(&input);
+        }
+    });
+    let stdout_reader = spawn_reader(child.stdout.take());
+    let stderr_reader = spawn_reader(child.stderr.take());
+
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if start.elapsed() >= timeout {
+            kill_process_group(&mut child);
+            let _ = child.wait();
+            timed_out = true;
+            break None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    let duration = start.elapsed();
+
+    let _ = stdin_writer.join();
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    Ok(HookRun {
+        exit_code: status.and_then(|s| s.code()),
+        stdout,
+        stderr,
+        duration,
+        timed_out,
+    })
+}
+
+/// Kill `child` and, on Unix, every process in its group
+fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pgid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: kill has no memory-safety preconditions; the group was
+        // created for this child by `process_group(0)`
+        unsafe {
+            libc::kill(-pgid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+}
+
+fn spawn_reader<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        String::from_utf8_lossy(&output).to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_kills_subshells() {
+        let env = BTreeMap::from([("GREETING".to_string(), "hi".to_string())]);
+        let start = Instant::now();
+        let run = run_hook_command(
+            "echo $GREETING; (sleep 5; echo done)",
+            b"{}",
+            Path::new("."),
+            &env,
+            Duration::from_millis(500),
+        )
+        .unwrap();
+
+        assert!(run.timed_out);
+        // The sleeping subshell holds the output pipes; it must die too
+        assert!(
+            start.elapsed() < Duration::from_secs(3),
+            "{:?}",
+            start.elapsed()
+        );
+        assert_eq!(run.stdout, "hi\n");
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::Result;
+use claco::hook_payload::{HookResponse, HookSpecificOutput, PermissionDecision};
+use claco::{HookEvent, ScriptLang};
+
+const BASH_TEMPLATE: &str = r#"#!/usr/bin/env bash
+# {{description}}
+# Reads the hook payload as JSON on stdin (needs jq).
+set -euo pipefail
+
+payload=$(cat)
+value=$(jq -r '.{{field}} // empty' <<<"$payload")
+
+# Allow: exit 0 without output and Claude carries on as usual
+allow() {
+  exit 0
+}
+
+# Block: exit 2 and the reason on stderr is fed back to Claude
+block() {
+  echo "$1" >&2
+  exit 2
+}
+
+# Feedback: print structured JSON on stdout and exit 0
+feedback() {
+  cat <<'JSON'
+{{feedback_pretty}}
+JSON
+  exit 0
+}
+
+# Replace these example checks with your own
+case "$value" in
+  *example-to-block*) block "Blocked by $(basename "$0"): $value" ;;
+  *example-for-feedback*) feedback ;;
+  *) allow ;;
+esac
+"#;
+
+const PYTHON_TEMPLATE: &str = r#"#!/usr/bin/env python3
+"""{{description}}"""
+import json
+import sys
+
+payload = json.load(sys.stdin)
+value = str(payload.get("{{field}}", ""))
+
+
+def allow():
+    """Exit 0 without output and Claude carries on as usual."""
+    sys.exit(0)
+
+
+def block(reason):
+    """Exit 2; the reason on stderr is fed back to Claude."""
+    print(reason, file=sys.stderr)
+    sys.exit(2)
+
+
+def feedback():
+    """Print structured JSON on stdout and exit 0."""
+    print(json.dumps({{feedback}}))
+    sys.exit(0)
+
+
+# Replace these example checks with your own
+if "example-to-block" in value:
+    block(f"Blocked by {sys.argv[0]}: {value}")
+elif "example-for-feedback" in value:
+    feedback()
+else:
+    allow()
+"#;
+
+const RUST_TEMPLATE: &str = r#"#!/usr/bin/env rust-script
+//! {{description}}
+//!
+//! ```cargo
+//! [dependencies]
+//! serde_json = "1"
+//! ```
+
+use serde_json::{json, Value};
+use std::io::Read;
+use std::process::exit;
+
+/// Exit 0 without output and Claude carries on as usual
+fn allow() -> ! {
+    exit(0)
+}
+
+/// Exit 2; the reason on stderr is fed back to Claude
+fn block(reason: &str) -> ! {
+    eprintln!("{reason}");
+    exit(2)
+}
+
+/// Print structured JSON on stdout and exit 0
+fn feedback() -> ! {
+    println!("{}", json!({{feedback}}));
+    exit(0)
+}
+
+fn main() {
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .expect("failed to read the hook payload");
+    let payload: Value = serde_json::from_str(&input).expect("invalid hook payload");
+    let value = payload["{{field}}"].to_string();
+
+    // Replace these example checks with your own
+    if value.contains("example-to-block") {
+        block(&format!("Blocked by hook: {value}"));
+    } else if value.contains("example-for-feedback") {
+        feedback();
+    } else {
+        allow();
+    }
+}
+"#;
+
+/// Default script name for a hook, e.g. `pre-tool-use-edit`
+pub fn default_script_name(event: &HookEvent, matcher: &str) -> String {
+    let mut name = String::new();
+    for (i, c) in event.as_str().chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            name.push('-');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+
+    // Matchers are regexes; keep only their word characters
+    let matcher: Vec<String> = matcher
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    if !matcher.is_empty() {
+        name.push('-');
+        name.push_str(&matcher.join("-"));
+    }
+    name
+}
+
+/// Payload field the scaffolded script inspects for an event
+fn payload_field(event: &HookEvent) -> &'static str {
+    match event {
+        HookEvent::PreToolUse | HookEvent::PostToolUse => "tool_input",
+        HookEvent::Notification => "message",
+        HookEvent::UserPromptSubmit => "prompt",
+        HookEvent::Stop | HookEvent::SubagentStop => "stop_hook_active",
+        HookEvent::PreCompact => "trigger",
+        HookEvent::SessionStart => "source",
+        HookEvent::SessionEnd => "reason",
+        _ => "hook_event_name",
+    }
+}
+
+/// Example structured response suited to an event
+fn example_feedback(event: &HookEvent) -> HookResponse {
+    match event {
+        HookEvent::PreToolUse => {
+            HookResponse::permission(PermissionDecision::Ask, "Please confirm this tool call")
+        }
+        HookEvent::PostToolUse => {
+            HookResponse::block("Please run the tests for the file you just changed")
+        }
+        HookEvent::UserPromptSubmit => HookResponse {
+            hook_specific_output: Some(HookSpecificOutput::UserPromptSubmit {
+                additional_context: Some("Extra context added to the prompt".to_string()),
+            }),
+            ..Default::default()
+        },
+        HookEvent::SessionStart => HookResponse {
+            hook_specific_output: Some(HookSpecificOutput::SessionStart {
+                additional_context: Some("Extra context for the new session".to_string()),
+            }),
+            ..Default::default()
+        },
+        HookEvent::Stop | HookEvent::SubagentStop => {
+            HookResponse::block("Keep going: the tests are still failing")
+        }
+        _ => HookResponse {
+            system_message: Some("Message shown to the user".to_string()),
+            ..Default::default()
+        },
+    }
+}
+
+/// Render a hook script for `event` in `lang`
+pub fn scaffold_script(lang: ScriptLang, event: &HookEvent, matcher: &str) -> Result<String> {
+    let template = match lang {
+        ScriptLang::Bash => BASH_TEMPLATE,
+        ScriptLang::Python => PYTHON_TEMPLATE,
+        ScriptLang::RustScript => RUST_TEMPLATE,
+    };
+    let description = if matcher.is_empty() {
+        format!("{event} hook scaffolded by `claco hooks scaffold`")
+    } else {
+        format!("{event} hook (matcher: {matcher}) scaffolded by `claco hooks scaffold`")
+    };
+    // The examples only hold strings, so the JSON is also a valid Python dict
+    let feedback = example_feedback(event);
+
+    Ok(template
+        .replace("{{description}}", &description)
+        .replace("{{field}}", payload_field(event))
+        .replace(
+            "{{feedback_pretty}}",
+            &serde_json::to_string_pretty(&feedback)?,
+        )
+        .replace("{{feedback}}", &serde_json::to_string(&feedback)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaffold_script() {
+        assert_eq!(
+            default_script_name(&HookEvent::PreToolUse, "Edit|MultiEdit"),
+            "pre-tool-use-edit-multiedit"
+        );
+        assert_eq!(default_script_name(&HookEvent::Stop, ""), "stop");
+
+        for lang in [ScriptLang::Bash, ScriptLang::Python, ScriptLang::RustScript] {
+            for event in &HookEvent::ALL {
+                let script = scaffold_script(lang, event, "Bash").unwrap();
+                assert!(
+                    !script.contains("{{"),
+                    "{lang:?} {event} left a placeholder"
+                );
+            }
+        }
+
+        let script = scaffold_script(ScriptLang::Python, &HookEvent::PreToolUse, "Edit").unwrap();
+        assert!(script.contains(r#"payload.get("tool_input", "")"#));
+        assert!(script.contains(r#""permissionDecision":"ask""#));
+    }
+}
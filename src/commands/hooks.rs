@@ -1,46 +1,127 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use claco::hook_payload::{
+    HookCommon, HookPayload, NotificationPayload, PostToolUsePayload, PreCompactPayload,
+    PreToolUsePayload, SessionEndPayload, SessionStartPayload, StopPayload,
+    UserPromptSubmitPayload,
+};
 use claco::{
-    load_settings, project_settings_path, save_settings, user_settings_path, Hook, HookMatcher,
-    HooksAction,
+    load_settings, managed_settings_path, project_dir, save_settings_backed_up,
+    settings_files_by_precedence, Hook, HookEvent, HookId, HookMatcher, Hooks, HooksAction, Scope,
+    ScriptLang, Settings,
 };
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::format_timestamp_local;
+use super::hook_edit::{
+    handle_hooks_disable, handle_hooks_enable, handle_hooks_move, handle_hooks_rm, hook_ids,
+    insert_hook, print_disabled_hooks,
+};
+use super::hook_exec::{hook_log_path, read_hook_log, HookLogRecord};
+use super::hook_lint::{configured_mcp_servers, lint_command, lint_matcher, Severity};
+use super::hook_recipes::{find_recipe, Recipe, RECIPES};
+use super::hook_record::{handle_hooks_capture, handle_hooks_record};
+use super::hook_runner::{run_hook_command, DEFAULT_HOOK_TIMEOUT_SECS};
+use super::hook_scaffold::{default_script_name, scaffold_script};
 
 /// Handle hook-related actions
 ///
 /// This function processes all hook management operations including:
-/// - Listing hooks from user/project scopes
+/// - Listing hooks per scope or merged across all scopes
 /// - Adding new hooks with event patterns and commands
-/// - Deleting hooks interactively
-pub fn handle_hooks(action: HooksAction) -> Result<()> {
+/// - Scaffolding hook scripts from templates
+/// - Deleting hooks interactively or by ID
+/// - Disabling, re-enabling and reordering hooks by ID
+/// - Installing and uninstalling built-in hook recipes
+/// - Linting matchers and commands
+/// - Querying the `hook-exec` audit log
+/// - Simulating events to test hooks locally
+/// - Recording real payloads as test fixtures
+pub async fn handle_hooks(action: HooksAction) -> Result<()> {
     match action {
-        HooksAction::List { scope } => handle_hooks_list(scope),
+        HooksAction::List { scope, effective } => handle_hooks_list(scope, effective),
         HooksAction::Add {
             scope,
             event,
             matcher,
             command,
-        } => handle_hooks_add(scope, event, matcher, command),
+            hook_type,
+            timeout,
+            fields,
+        } => {
+            let hook = build_hook(hook_type, command, timeout, fields)?;
+            handle_hooks_add(scope, event, matcher, hook)
+        }
+        HooksAction::Scaffold {
+            event,
+            matcher,
+            lang,
+            name,
+            scope,
+            force,
+        } => handle_hooks_scaffold(event, matcher, lang, name, scope, force),
         HooksAction::Delete { interactive } => handle_hooks_delete(interactive),
+        HooksAction::Rm { id } => handle_hooks_rm(&id),
+        HooksAction::Disable { id } => handle_hooks_disable(&id),
+        HooksAction::Enable { id } => handle_hooks_enable(&id),
+        HooksAction::Move { id, before } => handle_hooks_move(&id, &before),
+        HooksAction::Install {
+            recipe,
+            scope,
+            params,
+        } => match recipe {
+            Some(recipe) => handle_hooks_install(&recipe, scope, params),
+            None => {
+                print_recipes();
+                Ok(())
+            }
+        },
+        HooksAction::Uninstall { recipe, scope } => handle_hooks_uninstall(&recipe, scope),
+        HooksAction::Lint { strict } => handle_hooks_lint(strict),
+        HooksAction::Log {
+            event,
+            tool,
+            failed,
+            limit,
+            stats,
+        } => handle_hooks_log(event, tool, failed, limit, stats),
+        HooksAction::Test {
+            event,
+            tool,
+            input,
+            payload,
+        } => handle_hooks_test(event, tool, input, payload),
+        HooksAction::Record {
+            event,
+            out,
+            matcher,
+            scope,
+        } => handle_hooks_record(event, out, matcher, scope).await,
+        HooksAction::Capture { out } => handle_hooks_capture(&out),
     }
 }
 
-fn handle_hooks_list(scope: Option<String>) -> Result<()> {
+/// Editable settings scopes, in the order hooks are listed
+const HOOK_SCOPES: [Scope; 3] = [Scope::User, Scope::Project, Scope::ProjectLocal];
+
+fn handle_hooks_list(scope: Option<Scope>, effective: bool) -> Result<()> {
+    if effective {
+        return handle_hooks_list_effective();
+    }
+
     match scope {
         Some(specific_scope) => {
             // Show hooks for a specific scope
-            let settings_path = match specific_scope.as_str() {
-                "user" => user_settings_path()?,
-                "project" => project_settings_path(),
-                _ => {
-                    eprintln!("error: invalid scope '{specific_scope}' - use 'user' or 'project'");
-                    return Ok(());
-                }
-            };
-
+            let settings_path = specific_scope.settings_path()?;
             let settings = load_settings(&settings_path)?;
+            let label = specific_scope.label();
 
             if let Some(hooks) = &settings.hooks {
-                println!("Hooks in {specific_scope} scope:");
+                println!("Hooks in {label} scope:");
                 println!("Settings file: {}", settings_path.display());
                 println!();
 
@@ -49,127 +130,614 @@ fn handle_hooks_list(scope: Option<String>) -> Result<()> {
                     return Ok(());
                 }
 
-                for (event, matchers) in hooks {
+                for (event, matchers) in sorted_hooks(hooks) {
                     println!("Event: {event}");
-                    for matcher in matchers {
-                        for hook in &matcher.hooks {
-                            let mut parts = vec![];
-                            if !matcher.matcher.is_empty() {
-                                parts.push(format!("matcher={}", matcher.matcher));
-                            }
-                            if !hook.command.is_empty() {
-                                parts.push(format!("command=\"{}\"", hook.command));
-                            }
-                            if !hook.hook_type.is_empty() && hook.hook_type != "command" {
-                                parts.push(format!("type={}", hook.hook_type));
-                            }
-                            println!("  {}", parts.join(" "));
-                        }
+                    for (id, matcher, hook) in hook_ids(&specific_scope, event, matchers) {
+                        println!("  {id}  {}", format_hook(matcher, hook));
                     }
                     println!();
                 }
             } else {
-                println!("No hooks found in {specific_scope} scope");
+                println!("No hooks found in {label} scope");
             }
+
+            print_disabled_hooks(std::slice::from_ref(&specific_scope))?;
         }
         None => {
-            // Show hooks from both user and project scopes
-            // List user hooks
-            let user_settings_path = user_settings_path()?;
-            let user_settings = load_settings(&user_settings_path)?;
-
-            if let Some(hooks) = &user_settings.hooks {
-                if !hooks.is_empty() {
-                    println!("User hooks: {}", user_settings_path.display());
-                    for (event, matchers) in hooks {
-                        println!("  Event: {event}");
-                        for matcher in matchers {
-                            for hook in &matcher.hooks {
-                                let mut parts = vec![];
-                                if !matcher.matcher.is_empty() {
-                                    parts.push(format!("matcher={}", matcher.matcher));
-                                }
-                                if !hook.command.is_empty() {
-                                    parts.push(format!("command=\"{}\"", hook.command));
-                                }
-                                if !hook.hook_type.is_empty() && hook.hook_type != "command" {
-                                    parts.push(format!("type={}", hook.hook_type));
-                                }
-                                println!("    {}", parts.join(" "));
-                            }
-                        }
+            // Show hooks from every editable scope with their IDs
+            let mut found = false;
+            for scope in &HOOK_SCOPES {
+                let settings_path = scope.settings_path()?;
+                let settings = load_settings(&settings_path)?;
+                let hooks = match settings.hooks.as_ref().filter(|hooks| !hooks.is_empty()) {
+                    Some(hooks) => hooks,
+                    None => continue,
+                };
+
+                found = true;
+                println!("Hooks ({}): {}", scope.label(), settings_path.display());
+                for (event, matchers) in sorted_hooks(hooks) {
+                    println!("  Event: {event}");
+                    for (id, matcher, hook) in hook_ids(scope, event, matchers) {
+                        println!("    {id}  {}", format_hook(matcher, hook));
                     }
-                    println!();
                 }
+                println!();
             }
 
-            // List project hooks
-            let project_settings_path = project_settings_path();
-            let project_settings = load_settings(&project_settings_path)?;
-
-            if let Some(hooks) = &project_settings.hooks {
-                if !hooks.is_empty() {
-                    println!("Project hooks: {}", project_settings_path.display());
-                    for (event, matchers) in hooks {
-                        println!("  Event: {event}");
-                        for matcher in matchers {
-                            for hook in &matcher.hooks {
-                                let mut parts = vec![];
-                                if !matcher.matcher.is_empty() {
-                                    parts.push(format!("matcher={}", matcher.matcher));
-                                }
-                                if !hook.command.is_empty() {
-                                    parts.push(format!("command=\"{}\"", hook.command));
-                                }
-                                if !hook.hook_type.is_empty() && hook.hook_type != "command" {
-                                    parts.push(format!("type={}", hook.hook_type));
-                                }
-                                println!("    {}", parts.join(" "));
-                            }
+            // Managed settings are read-only, so their hooks have no IDs
+            let managed_path = managed_settings_path();
+            let managed = load_settings(&managed_path)?;
+            if let Some(hooks) = managed.hooks.as_ref().filter(|hooks| !hooks.is_empty()) {
+                found = true;
+                println!("Hooks (managed): {}", managed_path.display());
+                for (event, matchers) in sorted_hooks(hooks) {
+                    println!("  Event: {event}");
+                    for matcher in matchers {
+                        for hook in &matcher.hooks {
+                            println!("    {}", format_hook(matcher, hook));
                         }
                     }
-                } else {
+                }
+                println!();
+            }
+
+            if !found {
+                println!("No hooks found");
+            }
+
+            print_disabled_hooks(&HOOK_SCOPES)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Show the hooks Claude Code will actually run, merged across every scope
+///
+/// Hooks from all scopes are combined rather than overridden, so each hook is
+/// labelled with its source and identical hooks defined in more than one scope
+/// are flagged.
+fn handle_hooks_list_effective() -> Result<()> {
+    let mut loaded = Vec::new();
+
+    println!("Settings files (highest precedence first):");
+    for (label, settings_path) in settings_files_by_precedence()? {
+        let status = if settings_path.exists() {
+            ""
+        } else {
+            " (not found)"
+        };
+        println!("  [{label}] {}{status}", settings_path.display());
+        loaded.push((label, load_settings(&settings_path)?));
+    }
+    println!();
+
+    let merged = effective_hooks(&loaded);
+    if merged.is_empty() {
+        println!("No hooks found");
+        return Ok(());
+    }
+
+    let mut duplicates = 0;
+    for (event, hooks) in &merged {
+        println!("Event: {event}");
+        for entry in hooks {
+            let hook = format_hook(entry.matcher, entry.hook);
+            let id = match &entry.id {
+                Some(id) => format!("{id}  "),
+                None => String::new(),
+            };
+            match entry.duplicate_of {
+                Some(original) => {
+                    duplicates += 1;
                     println!(
-                        "No project hooks found at: {}",
-                        project_settings_path.display()
+                        "  [{}] {id}{hook} (duplicate of [{original}])",
+                        entry.source
                     );
                 }
-            } else {
-                println!(
-                    "No project hooks found at: {}",
-                    project_settings_path.display()
-                );
+                None => println!("  [{}] {id}{hook}", entry.source),
             }
         }
+        println!();
+    }
+
+    if duplicates > 0 {
+        println!("{duplicates} duplicate hook(s) defined in more than one place");
     }
 
     Ok(())
 }
 
-fn handle_hooks_add(scope: String, event: String, matcher: String, command: String) -> Result<()> {
-    let settings_path = match scope.as_str() {
-        "user" => user_settings_path()?,
-        "project" => project_settings_path(),
-        _ => {
-            eprintln!("error: invalid scope '{scope}' - use 'user' or 'project'");
-            return Ok(());
+/// A hook as Claude Code sees it after combining every settings file
+#[derive(Debug)]
+struct EffectiveHook<'a> {
+    /// Label of the settings file the hook is defined in
+    source: &'static str,
+    /// `None` for managed hooks, which claco cannot edit
+    id: Option<HookId>,
+    matcher: &'a HookMatcher,
+    hook: &'a Hook,
+    /// Source of an identical hook listed earlier, if any
+    duplicate_of: Option<&'static str>,
+}
+
+/// Combine the hooks of settings files given highest precedence first
+///
+/// Every hook is kept in precedence order; a hook with the same matcher and
+/// definition as one listed before it is marked as a duplicate.
+fn effective_hooks<'a>(
+    files: &'a [(&'static str, Settings)],
+) -> BTreeMap<HookEvent, Vec<EffectiveHook<'a>>> {
+    let mut merged: BTreeMap<HookEvent, Vec<EffectiveHook>> = BTreeMap::new();
+
+    for (source, settings) in files {
+        let scope = HOOK_SCOPES.iter().find(|scope| scope.label() == *source);
+        for (event, matchers) in settings.hooks.iter().flatten() {
+            let hooks = merged.entry(event.clone()).or_default();
+            let entries: Vec<(Option<HookId>, &HookMatcher, &Hook)> = match scope {
+                Some(scope) => hook_ids(scope, event, matchers)
+                    .into_iter()
+                    .map(|(id, matcher, hook)| (Some(id), matcher, hook))
+                    .collect(),
+                None => matchers
+                    .iter()
+                    .flat_map(|matcher| matcher.hooks.iter().map(move |hook| (None, matcher, hook)))
+                    .collect(),
+            };
+            for (id, matcher, hook) in entries {
+                let duplicate_of = hooks
+                    .iter()
+                    .find(|earlier| {
+                        earlier.matcher.matcher == matcher.matcher && earlier.hook == hook
+                    })
+                    .map(|earlier| earlier.source);
+                hooks.push(EffectiveHook {
+                    source,
+                    id,
+                    matcher,
+                    hook,
+                    duplicate_of,
+                });
+            }
+        }
+    }
+
+    merged
+}
+
+fn print_recipes() {
+    println!("Available recipes:");
+    for recipe in RECIPES {
+        println!("  {:<12} {}", recipe.name, recipe.description);
+        for param in recipe.params {
+            println!(
+                "    --param {}=...  {} (default: {})",
+                param.name, param.description, param.default
+            );
+        }
+    }
+    println!();
+    println!("Install with: claco hooks install <recipe> [--scope user|project|project.local]");
+}
+
+fn find_recipe_or_bail(name: &str) -> Result<&'static Recipe> {
+    find_recipe(name).ok_or_else(|| {
+        let names: Vec<&str> = RECIPES.iter().map(|recipe| recipe.name).collect();
+        anyhow::anyhow!(
+            "unknown recipe '{name}' - available recipes are: {}",
+            names.join(", ")
+        )
+    })
+}
+
+/// Where a hook script named `file_name` lives and the hook command that runs it
+///
+/// Project scopes reference the script through `$CLAUDE_PROJECT_DIR` so the
+/// hook works from any subdirectory; user scope uses an absolute path.
+fn hook_script_location(file_name: &str, scope: &Scope) -> Result<(PathBuf, String)> {
+    let settings_path = scope.settings_path()?;
+    let hooks_dir = settings_path
+        .parent()
+        .map(|dir| dir.join("hooks"))
+        .unwrap_or_else(|| PathBuf::from(".claude/hooks"));
+    let script_path = hooks_dir.join(file_name);
+
+    let command = match scope {
+        Scope::User => format!("\"{}\"", script_path.display()),
+        Scope::Project | Scope::ProjectLocal => {
+            format!("\"$CLAUDE_PROJECT_DIR\"/.claude/hooks/{file_name}")
         }
     };
 
-    // Validate event type
-    let valid_events = vec![
-        "PreToolUse",
-        "ToolPattern",
-        "Notification",
-        "Stop",
-        "SubagentStop",
-        "PreCompact",
-    ];
-    if !valid_events.contains(&event.as_str()) {
-        eprintln!("error: invalid event '{event}' - valid events are: {valid_events:?}");
+    Ok((script_path, command))
+}
+
+/// Write a hook script and mark it executable
+fn write_hook_script(script_path: &PathBuf, content: &str) -> Result<()> {
+    if let Some(parent) = script_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(script_path, content)
+        .with_context(|| format!("Failed to write {}", script_path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(script_path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// Hook command a recipe installs in the given scope
+fn recipe_command(recipe: &Recipe, scope: &Scope) -> Result<String> {
+    match recipe.command {
+        Some(command) => Ok(command.to_string()),
+        None => Ok(hook_script_location(&recipe.script_name(), scope)?.1),
+    }
+}
+
+fn handle_hooks_install(name: &str, scope: Scope, params: Vec<String>) -> Result<()> {
+    let recipe = find_recipe_or_bail(name)?;
+
+    let mut values = HashMap::new();
+    for param in params {
+        let (key, value) = param
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("invalid parameter '{param}' - expected KEY=VALUE"))?;
+        values.insert(key.to_string(), value.to_string());
+    }
+
+    // Write the helper script first so the hook never points at a missing file
+    if let Some(script) = recipe.render_script(&values)? {
+        let (script_path, _) = hook_script_location(&recipe.script_name(), &scope)?;
+        write_hook_script(&script_path, &script)?;
+        println!("Wrote helper script: {}", script_path.display());
+    }
+
+    let command = recipe_command(recipe, &scope)?;
+    let settings_path = scope.settings_path()?;
+    let mut settings = load_settings(&settings_path)?;
+    let hooks = settings.hooks.get_or_insert_with(Default::default);
+
+    // Reinstalling only refreshes the script and keeps the existing hook
+    let installed = hooks.get(&recipe.event).is_some_and(|matchers| {
+        matchers.iter().any(|matcher| {
+            matcher.matcher == recipe.matcher
+                && matcher.hooks.iter().any(|hook| hook.command == command)
+        })
+    });
+    if installed {
+        println!(
+            "Recipe '{}' is already installed in {} scope",
+            recipe.name,
+            scope.label()
+        );
         return Ok(());
     }
 
+    let mut hook = Hook::new_command(command);
+    hook.timeout = recipe.timeout;
+    let display = format_hook(
+        &HookMatcher {
+            matcher: recipe.matcher.to_string(),
+            hooks: Vec::new(),
+            other: Default::default(),
+        },
+        &hook,
+    );
+    insert_hook(hooks, &recipe.event, recipe.matcher, usize::MAX, hook);
+    save_settings_backed_up(&settings_path, &settings)?;
+
+    println!(
+        "Installed recipe '{}': {} -> {display}",
+        recipe.name, recipe.event
+    );
+    println!("Settings file: {}", settings_path.display());
+
+    Ok(())
+}
+
+fn handle_hooks_uninstall(name: &str, scope: Scope) -> Result<()> {
+    let recipe = find_recipe_or_bail(name)?;
+    let command = recipe_command(recipe, &scope)?;
+    let settings_path = scope.settings_path()?;
+    let mut settings = load_settings(&settings_path)?;
+
+    // Only the hook the recipe installed goes; the same command may have been
+    // added by hand for other events or matchers
+    let mut removed = 0;
+    if let Some(hooks) = settings.hooks.as_mut() {
+        if let Some(matchers) = hooks.get_mut(&recipe.event) {
+            for matcher in matchers
+                .iter_mut()
+                .filter(|matcher| matcher.matcher == recipe.matcher)
+            {
+                let before = matcher.hooks.len();
+                matcher.hooks.retain(|hook| hook.command != command);
+                removed += before - matcher.hooks.len();
+            }
+            matchers.retain(|matcher| !matcher.hooks.is_empty());
+            if matchers.is_empty() {
+                hooks.remove(&recipe.event);
+            }
+        }
+    }
+    if settings
+        .hooks
+        .as_ref()
+        .is_some_and(|hooks| hooks.is_empty())
+    {
+        settings.hooks = None;
+    }
+
+    if removed > 0 {
+        save_settings_backed_up(&settings_path, &settings)?;
+        println!(
+            "Removed {removed} hook(s) installed by recipe '{}' from {}",
+            recipe.name,
+            settings_path.display()
+        );
+    }
+
+    // Keep the helper script while other hooks still run it
+    let still_used = settings
+        .hooks
+        .iter()
+        .flat_map(|hooks| hooks.values())
+        .any(|matchers| {
+            matchers
+                .iter()
+                .any(|matcher| matcher.hooks.iter().any(|hook| hook.command == command))
+        });
+    if recipe.script.is_some() && !still_used {
+        let (script_path, _) = hook_script_location(&recipe.script_name(), &scope)?;
+        if script_path.exists() {
+            fs::remove_file(&script_path)
+                .with_context(|| format!("Failed to remove {}", script_path.display()))?;
+            println!("Removed helper script: {}", script_path.display());
+            removed += 1;
+
+            // Leave no empty hooks directory behind; it may hold other scripts
+            if let Some(hooks_dir) = script_path.parent() {
+                let _ = fs::remove_dir(hooks_dir);
+            }
+        }
+    }
+
+    if removed == 0 {
+        println!(
+            "Recipe '{}' is not installed in {} scope",
+            recipe.name,
+            scope.label()
+        );
+    }
+
+    Ok(())
+}
+
+/// Lint hooks in every settings scope
+///
+/// Prints one line per problem and fails when errors are found, or when
+/// warnings are found in strict mode, so it can gate CI.
+fn handle_hooks_lint(strict: bool) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let mcp_servers = configured_mcp_servers(&cwd);
+
+    let mut checked = 0;
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for (label, settings_path) in settings_files_by_precedence()? {
+        let settings = load_settings(&settings_path)?;
+        let hooks = match &settings.hooks {
+            Some(hooks) => hooks,
+            None => continue,
+        };
+
+        for (event, matchers) in sorted_hooks(hooks) {
+            for matcher in matchers {
+                let mut issues = lint_matcher(event, &matcher.matcher, &mcp_servers);
+                for hook in &matcher.hooks {
+                    checked += 1;
+                    if hook.hook_type == "command" {
+                        issues.extend(lint_command(&hook.command, &cwd));
+                    }
+                }
+
+                let location = if matcher.matcher.is_empty() {
+                    format!("[{label}] {event}")
+                } else {
+                    format!("[{label}] {event} {}", matcher.matcher)
+                };
+                for issue in issues {
+                    match issue.severity {
+                        Severity::Error => errors += 1,
+                        Severity::Warning => warnings += 1,
+                    }
+                    println!("{}: {location}: {}", issue.severity.label(), issue.message);
+                }
+            }
+        }
+    }
+
+    println!("Checked {checked} hook(s): {errors} error(s), {warnings} warning(s)");
+
+    if errors > 0 || (strict && warnings > 0) {
+        anyhow::bail!("hooks lint failed");
+    }
+
+    Ok(())
+}
+
+/// Show or summarize hook invocations recorded by `hook-exec`
+fn handle_hooks_log(
+    event: Option<HookEvent>,
+    tool: Option<String>,
+    failed: bool,
+    limit: usize,
+    stats: bool,
+) -> Result<()> {
+    let all_records = read_hook_log()?;
+    if all_records.is_empty() {
+        println!(
+            "No hook invocations recorded in {}",
+            hook_log_path()?.display()
+        );
+        println!("Wrap a hook command with `claco hook-exec -- <command>` to record it");
+        return Ok(());
+    }
+
+    let records: Vec<HookLogRecord> = all_records
+        .into_iter()
+        .filter(|r| {
+            event
+                .as_ref()
+                .is_none_or(|e| r.event.as_deref() == Some(e.as_str()))
+        })
+        .filter(|r| tool.is_none() || r.tool == tool)
+        .filter(|r| !failed || r.failed() || r.blocked())
+        .collect();
+
+    if records.is_empty() {
+        println!("No matching hook invocations");
+        return Ok(());
+    }
+
+    if stats {
+        // Per command: runs, failures, total and max duration
+        let mut summary: BTreeMap<&str, (usize, usize, u64, u64)> = BTreeMap::new();
+        for record in &records {
+            let entry = summary.entry(record.command.as_str()).or_default();
+            entry.0 += 1;
+            if record.failed() {
+                entry.1 += 1;
+            }
+            entry.2 += record.duration_ms;
+            entry.3 = entry.3.max(record.duration_ms);
+        }
+
+        let mut summary: Vec<_> = summary.into_iter().collect();
+        summary.sort_by_key(|(_, (_, _, total, _))| std::cmp::Reverse(*total));
+        for (command, (runs, failures, total, max)) in summary {
+            println!("{command}");
+            println!(
+                "  {runs} run(s), {failures} failed, avg {}ms, max {max}ms, total {total}ms",
+                total / runs as u64
+            );
+        }
+        return Ok(());
+    }
+
+    let skip = records.len().saturating_sub(limit);
+    for record in &records[skip..] {
+        let mut line = format!(
+            "{} {}",
+            format_timestamp_local(&record.timestamp),
+            record.event.as_deref().unwrap_or("?")
+        );
+        if let Some(ref tool) = record.tool {
+            line.push_str(&format!(" {tool}"));
+        }
+        if let Some(ref matcher) = record.matcher {
+            line.push_str(&format!(" (matcher={matcher})"));
+        }
+        let exit = match (record.timed_out, record.exit_code) {
+            (true, _) => "timed out".to_string(),
+            (false, Some(code)) => format!("exit {code}"),
+            (false, None) => "killed".to_string(),
+        };
+        line.push_str(&format!(" {}ms {exit}", record.duration_ms));
+        if let Some(ref decision) = record.decision {
+            line.push_str(&format!(" decision={decision}"));
+        }
+        println!("{line}");
+        println!("  {}", record.command);
+    }
+
+    Ok(())
+}
+
+/// Hook events sorted for stable display
+fn sorted_hooks(hooks: &Hooks) -> Vec<(&HookEvent, &Vec<HookMatcher>)> {
+    let mut sorted: Vec<_> = hooks.iter().collect();
+    sorted.sort_by_key(|(event, _)| *event);
+    sorted
+}
+
+fn handle_hooks_add(scope: Scope, event: HookEvent, matcher: String, hook: Hook) -> Result<()> {
+    if !check_hook_matcher(&event, &matcher)? {
+        return Ok(());
+    }
+
+    if hook.hook_type == "command" && hook.command.is_empty() {
+        eprintln!("error: --command is required for command hooks");
+        return Ok(());
+    }
+
+    if hook.hook_type == "command" {
+        for issue in lint_command(&hook.command, &std::env::current_dir()?) {
+            eprintln!("warning: {}", issue.message);
+        }
+    }
+
+    add_hook(scope, event, matcher, hook)
+}
+
+fn handle_hooks_scaffold(
+    event: HookEvent,
+    matcher: String,
+    lang: ScriptLang,
+    name: Option<String>,
+    scope: Scope,
+    force: bool,
+) -> Result<()> {
+    // Validate before writing so a rejected hook leaves no script behind
+    if !check_hook_matcher(&event, &matcher)? {
+        return Ok(());
+    }
+
+    let name = name.unwrap_or_else(|| default_script_name(&event, &matcher));
+    let file_name = format!("{name}.{}", lang.extension());
+    let (script_path, command) = hook_script_location(&file_name, &scope)?;
+    if script_path.exists() && !force {
+        eprintln!(
+            "error: {} already exists - use --force to overwrite it or --name to pick another name",
+            script_path.display()
+        );
+        return Ok(());
+    }
+
+    write_hook_script(&script_path, &scaffold_script(lang, &event, &matcher)?)?;
+    println!("Wrote hook script: {}", script_path.display());
+
+    add_hook(scope, event, matcher, Hook::new_command(command))
+}
+
+/// Check that hooks can be registered for `matcher` under `event`
+///
+/// Prints errors and warnings, returning false if the hook must be rejected.
+fn check_hook_matcher(event: &HookEvent, matcher: &str) -> Result<bool> {
+    // Only some events are filtered by a matcher
+    if !matcher.is_empty() && !event.uses_matcher() {
+        eprintln!("error: event '{event}' does not use matchers - omit --matcher");
+        return Ok(false);
+    }
+
+    // Reject matchers that can never work and warn about suspicious ones
+    let cwd = std::env::current_dir()?;
+    for issue in lint_matcher(event, matcher, &configured_mcp_servers(&cwd)) {
+        if issue.severity == Severity::Error {
+            eprintln!("error: {}", issue.message);
+            return Ok(false);
+        }
+        eprintln!("warning: {}", issue.message);
+    }
+    Ok(true)
+}
+
+/// Append a hook to the settings file of `scope`
+fn add_hook(scope: Scope, event: HookEvent, matcher: String, hook: Hook) -> Result<()> {
+    let settings_path = scope.settings_path()?;
     let mut settings = load_settings(&settings_path)?;
 
     // Initialize hooks if not present
@@ -185,103 +753,110 @@ fn handle_hooks_add(scope: String, event: String, matcher: String, command: Stri
     // Find existing matcher or create new one
     let matcher_entry = event_matchers.iter_mut().find(|m| m.matcher == matcher);
 
+    let display = format_hook(
+        &HookMatcher {
+            matcher: matcher.clone(),
+            hooks: Vec::new(),
+            other: Default::default(),
+        },
+        &hook,
+    );
+
     if let Some(matcher_entry) = matcher_entry {
         // Add hook to existing matcher
-        matcher_entry.hooks.push(Hook {
-            hook_type: "command".to_string(),
-            command: command.clone(),
-        });
+        matcher_entry.hooks.push(hook);
     } else {
         // Create new matcher with the hook
         event_matchers.push(HookMatcher {
-            matcher: matcher.clone(),
-            hooks: vec![Hook {
-                hook_type: "command".to_string(),
-                command: command.clone(),
-            }],
+            matcher,
+            hooks: vec![hook],
+            other: Default::default(),
         });
     }
 
-    save_settings(&settings_path, &settings)?;
+    save_settings_backed_up(&settings_path, &settings)?;
 
-    let mut parts = vec![];
-    if !matcher.is_empty() {
-        parts.push(format!("matcher={matcher}"));
-    }
-    if !command.is_empty() {
-        parts.push(format!("command=\"{command}\""));
-    }
-    println!("Added hook: {} -> {}", event, parts.join(" "));
+    println!("Added hook: {event} -> {display}");
     println!("Settings file: {}", settings_path.display());
 
     Ok(())
 }
 
+/// Build a hook from `hooks add` arguments
+///
+/// Extra fields are given as `KEY=VALUE`, where VALUE is parsed as JSON and
+/// falls back to a plain string.
+fn build_hook(
+    hook_type: String,
+    command: Option<String>,
+    timeout: Option<u64>,
+    fields: Vec<String>,
+) -> Result<Hook> {
+    let mut hook = Hook::new_command(command.unwrap_or_default());
+    hook.hook_type = hook_type;
+    hook.timeout = timeout;
+
+    for field in fields {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("invalid field '{field}' - expected KEY=VALUE"))?;
+        if matches!(key, "type" | "command" | "timeout") {
+            anyhow::bail!("use --{key} instead of --set {key}=...");
+        }
+        let value = serde_json::from_str::<Value>(value)
+            .unwrap_or_else(|_| Value::String(value.to_string()));
+        hook.other.insert(key.to_string(), value);
+    }
+
+    Ok(hook)
+}
+
+/// Format a hook for display on a single line
+pub fn format_hook(matcher: &HookMatcher, hook: &Hook) -> String {
+    let mut parts = vec![];
+    if !matcher.matcher.is_empty() {
+        parts.push(format!("matcher={}", matcher.matcher));
+    }
+    if !hook.command.is_empty() {
+        parts.push(format!("command=\"{}\"", hook.command));
+    }
+    if !hook.hook_type.is_empty() && hook.hook_type != "command" {
+        parts.push(format!("type={}", hook.hook_type));
+    }
+    if let Some(timeout) = hook.timeout {
+        parts.push(format!("timeout={timeout}"));
+    }
+    let mut other: Vec<_> = hook.other.iter().collect();
+    other.sort_by_key(|(key, _)| key.as_str());
+    for (key, value) in other {
+        parts.push(format!("{key}={value}"));
+    }
+    parts.join(" ")
+}
+
 fn handle_hooks_delete(interactive: bool) -> Result<()> {
     if !interactive {
-        eprintln!("error: non-interactive mode is not supported yet");
+        eprintln!("error: use 'claco hooks rm <id>' to delete hooks non-interactively");
         return Ok(());
     }
 
-    // Load hooks from both scopes
-    let user_settings_path = user_settings_path()?;
-    let project_settings_path = project_settings_path();
-
-    let user_settings = load_settings(&user_settings_path)?;
-    let project_settings = load_settings(&project_settings_path)?;
-
     // Collect all hooks with their metadata
     let mut hooks_list = Vec::new();
 
-    // Add user hooks
-    if let Some(hooks) = &user_settings.hooks {
-        for (event, matchers) in hooks {
-            for (matcher_idx, matcher) in matchers.iter().enumerate() {
-                for (hook_idx, hook) in matcher.hooks.iter().enumerate() {
-                    let mut parts = vec![];
-                    if !matcher.matcher.is_empty() {
-                        parts.push(format!("matcher={}", matcher.matcher));
-                    }
-                    if !hook.command.is_empty() {
-                        parts.push(format!("command=\"{}\"", hook.command));
-                    }
-                    if !hook.hook_type.is_empty() && hook.hook_type != "command" {
-                        parts.push(format!("type={}", hook.hook_type));
-                    }
-                    hooks_list.push((
-                        parts.join(" "),
-                        "user".to_string(),
-                        event.clone(),
-                        matcher_idx,
-                        hook_idx,
-                    ));
-                }
-            }
-        }
-    }
-
-    // Add project hooks
-    if let Some(hooks) = &project_settings.hooks {
-        for (event, matchers) in hooks {
-            for (matcher_idx, matcher) in matchers.iter().enumerate() {
-                for (hook_idx, hook) in matcher.hooks.iter().enumerate() {
-                    let mut parts = vec![];
-                    if !matcher.matcher.is_empty() {
-                        parts.push(format!("matcher={}", matcher.matcher));
-                    }
-                    if !hook.command.is_empty() {
-                        parts.push(format!("command=\"{}\"", hook.command));
+    for scope in &HOOK_SCOPES {
+        let settings = load_settings(&scope.settings_path()?)?;
+        if let Some(hooks) = &settings.hooks {
+            for (event, matchers) in sorted_hooks(hooks) {
+                for (matcher_idx, matcher) in matchers.iter().enumerate() {
+                    for (hook_idx, hook) in matcher.hooks.iter().enumerate() {
+                        hooks_list.push((
+                            format_hook(matcher, hook),
+                            scope,
+                            event.clone(),
+                            matcher_idx,
+                            hook_idx,
+                        ));
                     }
-                    if !hook.hook_type.is_empty() && hook.hook_type != "command" {
-                        parts.push(format!("type={}", hook.hook_type));
-                    }
-                    hooks_list.push((
-                        parts.join(" "),
-                        "project".to_string(),
-                        event.clone(),
-                        matcher_idx,
-                        hook_idx,
-                    ));
                 }
             }
         }
@@ -295,7 +870,7 @@ fn handle_hooks_delete(interactive: bool) -> Result<()> {
     // Display hooks for selection
     println!("Select hooks to delete:");
     for (i, (hook_display, scope, event, _, _)) in hooks_list.iter().enumerate() {
-        println!("{}. [{}] {}: {}", i + 1, scope, event, hook_display);
+        println!("{}. [{}] {}: {}", i + 1, scope.label(), event, hook_display);
     }
 
     println!("\nEnter hook numbers to delete (comma-separated, or 'all' for all hooks):");
@@ -327,30 +902,30 @@ fn handle_hooks_delete(interactive: bool) -> Result<()> {
         return Ok(());
     }
 
-    // Group removals by scope
-    let mut user_removals = Vec::new();
-    let mut project_removals = Vec::new();
-
-    for &idx in &indices_to_delete {
-        let (_, scope, event, matcher_idx, hook_idx) = &hooks_list[idx];
-        match scope.as_str() {
-            "user" => user_removals.push((event.clone(), *matcher_idx, *hook_idx)),
-            "project" => project_removals.push((event.clone(), *matcher_idx, *hook_idx)),
-            _ => {}
+    // Remove selected hooks one scope at a time, last first so the
+    // remaining indices stay valid
+    for scope in &HOOK_SCOPES {
+        let mut removals: Vec<_> = indices_to_delete
+            .iter()
+            .map(|&idx| &hooks_list[idx])
+            .filter(|(_, s, _, _, _)| *s == scope)
+            .map(|(_, _, event, matcher_idx, hook_idx)| (event, *matcher_idx, *hook_idx))
+            .collect();
+        if removals.is_empty() {
+            continue;
         }
-    }
+        removals.sort();
 
-    // Remove from user settings
-    if !user_removals.is_empty() {
-        let mut user_settings = load_settings(&user_settings_path)?;
-        if let Some(hooks) = &mut user_settings.hooks {
-            for (event, matcher_idx, hook_idx) in user_removals.iter().rev() {
+        let settings_path = scope.settings_path()?;
+        let mut settings = load_settings(&settings_path)?;
+        if let Some(hooks) = &mut settings.hooks {
+            for (event, matcher_idx, hook_idx) in removals.into_iter().rev() {
                 if let Some(matchers) = hooks.get_mut(event) {
-                    if let Some(matcher) = matchers.get_mut(*matcher_idx) {
-                        if *hook_idx < matcher.hooks.len() {
-                            matcher.hooks.remove(*hook_idx);
+                    if let Some(matcher) = matchers.get_mut(matcher_idx) {
+                        if hook_idx < matcher.hooks.len() {
+                            matcher.hooks.remove(hook_idx);
                             if matcher.hooks.is_empty() {
-                                matchers.remove(*matcher_idx);
+                                matchers.remove(matcher_idx);
                             }
                         }
                     }
@@ -360,33 +935,322 @@ fn handle_hooks_delete(interactive: bool) -> Result<()> {
                 }
             }
         }
-        save_settings(&user_settings_path, &user_settings)?;
+        save_settings_backed_up(&settings_path, &settings)?;
     }
 
-    // Remove from project settings
-    if !project_removals.is_empty() {
-        let mut project_settings = load_settings(&project_settings_path)?;
-        if let Some(hooks) = &mut project_settings.hooks {
-            for (event, matcher_idx, hook_idx) in project_removals.iter().rev() {
-                if let Some(matchers) = hooks.get_mut(event) {
-                    if let Some(matcher) = matchers.get_mut(*matcher_idx) {
-                        if *hook_idx < matcher.hooks.len() {
-                            matcher.hooks.remove(*hook_idx);
-                            if matcher.hooks.is_empty() {
-                                matchers.remove(*matcher_idx);
-                            }
-                        }
-                    }
-                    if matchers.is_empty() {
-                        hooks.remove(event);
-                    }
+    println!("Deleted {} hooks", indices_to_delete.len());
+
+    Ok(())
+}
+
+/// Build the payload Claude Code sends on stdin for an event
+fn build_hook_payload(
+    event: &HookEvent,
+    cwd: &str,
+    tool: Option<&str>,
+    tool_input: Value,
+) -> Result<HookPayload> {
+    let session_id = "00000000-0000-0000-0000-000000000000";
+    let transcript_path = project_dir(cwd)?.join(format!("{session_id}.jsonl"));
+
+    let common = HookCommon {
+        session_id: session_id.to_string(),
+        transcript_path: transcript_path.to_string_lossy().to_string(),
+        cwd: cwd.to_string(),
+        permission_mode: Some("default".to_string()),
+    };
+    let tool_name = || {
+        tool.map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("--tool is required for {event}"))
+    };
+
+    Ok(match event {
+        HookEvent::PreToolUse => HookPayload::PreToolUse(PreToolUsePayload {
+            common,
+            tool_name: tool_name()?,
+            tool_input,
+        }),
+        HookEvent::PostToolUse => HookPayload::PostToolUse(PostToolUsePayload {
+            common,
+            tool_name: tool_name()?,
+            tool_input,
+            tool_response: json!({ "success": true }),
+        }),
+        HookEvent::Notification => HookPayload::Notification(NotificationPayload {
+            common,
+            message: "Claude needs your permission to use a tool".to_string(),
+        }),
+        HookEvent::UserPromptSubmit => HookPayload::UserPromptSubmit(UserPromptSubmitPayload {
+            common,
+            prompt: "Write a function to calculate the factorial".to_string(),
+        }),
+        HookEvent::Stop => HookPayload::Stop(StopPayload {
+            common,
+            stop_hook_active: false,
+        }),
+        HookEvent::SubagentStop => HookPayload::SubagentStop(StopPayload {
+            common,
+            stop_hook_active: false,
+        }),
+        HookEvent::PreCompact => HookPayload::PreCompact(PreCompactPayload {
+            common,
+            trigger: "manual".to_string(),
+            custom_instructions: String::new(),
+        }),
+        HookEvent::SessionStart => HookPayload::SessionStart(SessionStartPayload {
+            common,
+            source: "startup".to_string(),
+        }),
+        HookEvent::SessionEnd => HookPayload::SessionEnd(SessionEndPayload {
+            common,
+            reason: "other".to_string(),
+        }),
+        HookEvent::Unknown(name) => anyhow::bail!("cannot simulate unknown event '{name}'"),
+    })
+}
+
+/// Environment Claude Code gives hooks on top of its own
+///
+/// The `env` variables of the settings files (higher precedence wins),
+/// `CLAUDECODE`, `CLAUDE_CODE_ENTRYPOINT` and `CLAUDE_PROJECT_DIR`. Plugin
+/// variables and the `CLAUDE_ENV_FILE` of `SessionStart` hooks are not set.
+fn simulated_hook_env(project_dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut env = BTreeMap::new();
+    for (_, settings_path) in settings_files_by_precedence()?.iter().rev() {
+        let settings = load_settings(settings_path)?;
+        if let Some(Value::Object(vars)) = settings.other.get("env") {
+            for (name, value) in vars {
+                let value = match value {
+                    Value::String(value) => value.clone(),
+                    other => other.to_string(),
+                };
+                env.insert(name.clone(), value);
+            }
+        }
+    }
+    env.insert("CLAUDECODE".to_string(), "1".to_string());
+    env.insert("CLAUDE_CODE_ENTRYPOINT".to_string(), "cli".to_string());
+    env.insert(
+        "CLAUDE_PROJECT_DIR".to_string(),
+        project_dir.to_string_lossy().to_string(),
+    );
+    Ok(env)
+}
+
+fn handle_hooks_test(
+    event: HookEvent,
+    tool: Option<String>,
+    input: Option<String>,
+    payload_overrides: Option<String>,
+) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let cwd_str = cwd.to_string_lossy().to_string();
+
+    let tool_input = match input {
+        Some(input) => serde_json::from_str(&input).context("--input must be valid JSON")?,
+        None => json!({}),
+    };
+
+    let typed = build_hook_payload(&event, &cwd_str, tool.as_deref(), tool_input)?;
+    let mut payload = serde_json::to_value(&typed)?;
+
+    if let Some(overrides) = payload_overrides {
+        let overrides: Value =
+            serde_json::from_str(&overrides).context("--payload must be valid JSON")?;
+        match (payload.as_object_mut(), overrides.as_object()) {
+            (Some(payload), Some(overrides)) => payload.extend(overrides.clone()),
+            _ => anyhow::bail!("--payload must be a JSON object"),
+        }
+    }
+
+    // Overrides may change the fields matchers look at, so re-read them
+    let typed: HookPayload = serde_json::from_value(payload.clone())
+        .context("--payload produced an invalid hook payload")?;
+
+    println!("Payload:");
+    println!("{}", serde_json::to_string_pretty(&payload)?);
+    println!();
+
+    let value = typed.matcher_value();
+    let input = serde_json::to_vec(&payload)?;
+    let env = simulated_hook_env(&cwd)?;
+    let mut ran = 0;
+    let mut skipped = Vec::new();
+
+    for (scope_label, settings_path) in settings_files_by_precedence()? {
+        let settings = load_settings(&settings_path)?;
+        let matchers = match settings.hooks.as_ref().and_then(|hooks| hooks.get(&event)) {
+            Some(matchers) => matchers,
+            None => continue,
+        };
+
+        for matcher in matchers {
+            // Events without matchers fire every configured hook
+            if let Some(value) = value {
+                if !matcher.matches(value) {
+                    continue;
                 }
             }
+
+            for hook in &matcher.hooks {
+                println!(
+                    "[{}] {}: {}",
+                    scope_label,
+                    event,
+                    format_hook(matcher, hook)
+                );
+
+                if hook.hook_type != "command" {
+                    println!(
+                        "  skipped: {} hooks are evaluated by Claude Code, only command hooks can be run locally",
+                        hook.hook_type
+                    );
+                    println!();
+                    skipped.push(hook.hook_type.clone());
+                    continue;
+                }
+
+                let timeout =
+                    Duration::from_secs(hook.timeout.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS));
+                let run = run_hook_command(&hook.command, &input, &cwd, &env, timeout)?;
+                ran += 1;
+
+                println!("  exit code: {}", run.describe_exit());
+                println!("  duration: {}ms", run.duration.as_millis());
+                if !run.stdout.trim().is_empty() {
+                    println!("  stdout: {}", run.stdout.trim_end());
+                }
+                if !run.stderr.trim().is_empty() {
+                    println!("  stderr: {}", run.stderr.trim_end());
+                }
+                if let Some(decision) = run.response().and_then(|r| r.decision_label()) {
+                    println!("  decision: {decision}");
+                }
+                println!();
+            }
         }
-        save_settings(&project_settings_path, &project_settings)?;
     }
 
-    println!("Deleted {} hooks", indices_to_delete.len());
+    if ran == 0 && skipped.is_empty() {
+        println!("No hooks would fire for {event}");
+    } else if !skipped.is_empty() {
+        let count = skipped.len();
+        skipped.sort();
+        skipped.dedup();
+        println!(
+            "Ran {ran} command hook(s); {count} matching hook(s) of type {} were not run, as only Claude Code can evaluate them",
+            skipped.join(", ")
+        );
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_hook_payload() {
+        let payload = build_hook_payload(
+            &HookEvent::PreToolUse,
+            "/tmp/project",
+            Some("Bash"),
+            json!({"command": "ls"}),
+        )
+        .unwrap();
+        let value = serde_json::to_value(&payload).unwrap();
+        assert_eq!(value["hook_event_name"], "PreToolUse");
+        assert_eq!(value["cwd"], "/tmp/project");
+        assert_eq!(value["tool_input"]["command"], "ls");
+        assert_eq!(payload.matcher_value(), Some("Bash"));
+
+        // Tool events need a tool name
+        assert!(build_hook_payload(&HookEvent::PostToolUse, "/tmp", None, json!({})).is_err());
+
+        let payload = build_hook_payload(&HookEvent::PreCompact, "/tmp", None, json!({})).unwrap();
+        assert_eq!(payload.matcher_value(), Some("manual"));
+        let payload = build_hook_payload(&HookEvent::Stop, "/tmp", None, json!({})).unwrap();
+        assert_eq!(payload.matcher_value(), None);
+    }
+
+    #[test]
+    fn test_hook_id_parse() {
+        let id: HookId = "project.local/PreToolUse/mcp__.*/write/2".parse().unwrap();
+        assert_eq!(id.scope, Scope::ProjectLocal);
+        assert_eq!(id.event, HookEvent::PreToolUse);
+        assert_eq!(id.matcher, "mcp__.*/write");
+        assert_eq!(id.index, 2);
+        assert_eq!(id.to_string(), "project.local/PreToolUse/mcp__.*/write/2");
+
+        let id: HookId = "user/Stop//0".parse().unwrap();
+        assert_eq!(id.matcher, "");
+
+        assert!("user/Stop/0".parse::<HookId>().is_err());
+        assert!("user//Bash/0".parse::<HookId>().is_err());
+        assert!("global/Stop//0".parse::<HookId>().is_err());
+        // `hooks list` prints IDs for events claco does not know, so they parse too
+        let id: HookId = "user/OnSave//0".parse().unwrap();
+        assert_eq!(id.event, HookEvent::Unknown("OnSave".to_string()));
+        assert_eq!(id.to_string(), "user/OnSave//0");
+        assert!("user/Stop//first".parse::<HookId>().is_err());
+    }
+
+    #[test]
+    fn test_effective_hooks() {
+        let stop = |command: &str| json!({"hooks": {"Stop": [{"hooks": [{"type": "command", "command": command}]}]}});
+        let bash = |command: &str| json!({"matcher": "Bash", "hooks": [{"type": "command", "command": command}]});
+        let files: Vec<(&'static str, Settings)> = [
+            ("managed", stop("audit")),
+            (
+                "project.local",
+                json!({"hooks": {"PreToolUse": [bash("lint"), bash("guard")]}}),
+            ),
+            ("project", json!({})),
+            (
+                "user",
+                json!({"hooks": {"PreToolUse": [bash("guard")], "Stop": [{"hooks": [{"type": "command", "command": "audit"}]}]}}),
+            ),
+        ]
+        .into_iter()
+        .map(|(label, value)| (label, serde_json::from_value(value).unwrap()))
+        .collect();
+
+        let merged = effective_hooks(&files);
+        let summary = |event: &HookEvent| -> Vec<String> {
+            merged[event]
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "[{}] {} {} {:?}",
+                        entry.source,
+                        entry
+                            .id
+                            .as_ref()
+                            .map_or("-".to_string(), |id| id.to_string()),
+                        entry.hook.command,
+                        entry.duplicate_of
+                    )
+                })
+                .collect()
+        };
+
+        // A matcher defined in both project.local and user runs the hooks of both
+        assert_eq!(
+            summary(&HookEvent::PreToolUse),
+            vec![
+                "[project.local] project.local/PreToolUse/Bash/0 lint None",
+                "[project.local] project.local/PreToolUse/Bash/1 guard None",
+                r#"[user] user/PreToolUse/Bash/0 guard Some("project.local")"#,
+            ]
+        );
+        // Managed hooks come first and have no ID, as they can't be edited
+        assert_eq!(
+            summary(&HookEvent::Stop),
+            vec![
+                "[managed] - audit None",
+                r#"[user] user/Stop//0 audit Some("managed")"#,
+            ]
+        );
+    }
+}
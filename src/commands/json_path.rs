@@ -0,0 +1,260 @@
+use anyhow::{anyhow, Result};
+use jsonpath_rust::parser::model::{JsonPath, JsonPathIndex};
+use jsonpath_rust::{JsonPathFinder, JsonPathInst};
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+/// One step of a plain JSONPath such as `$.env.FOO` or `$.permissions.allow[0]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Accept `env.FOO` as shorthand for `$.env.FOO`
+fn normalize(path: &str) -> String {
+    if path.starts_with('$') {
+        path.to_string()
+    } else {
+        format!("$.{path}")
+    }
+}
+
+/// Evaluate a JSONPath query, returning every match
+///
+/// Any expression `jsonpath-rust` supports works here, including wildcards,
+/// recursive descent and filters.
+pub fn query(value: &Value, path: &str) -> Result<Vec<Value>> {
+    let path = JsonPathInst::from_str(&normalize(path))
+        .map_err(|e| anyhow!("invalid JSONPath '{path}': {e}"))?;
+    let finder = JsonPathFinder::new(Box::new(value.clone()), Box::new(path));
+    Ok(match finder.find() {
+        Value::Array(matches) => matches,
+        _ => Vec::new(),
+    })
+}
+
+/// Parse a JSONPath that addresses exactly one location
+///
+/// Editing needs a single target, so wildcards, slices, filters and
+/// recursive descent are rejected.
+pub fn parse_path(path: &str) -> Result<Vec<PathSegment>> {
+    let parsed = JsonPath::try_from(normalize(path).as_str())
+        .map_err(|e| anyhow!("invalid JSONPath '{path}': {e}"))?;
+
+    let steps = match parsed {
+        JsonPath::Chain(steps) => steps,
+        step => vec![step],
+    };
+
+    let mut segments = Vec::new();
+    for step in steps {
+        match step {
+            JsonPath::Root => {}
+            JsonPath::Field(key) => segments.push(PathSegment::Key(key)),
+            JsonPath::Index(JsonPathIndex::UnionKeys(keys)) if keys.len() == 1 => {
+                segments.push(PathSegment::Key(keys[0].clone()))
+            }
+            JsonPath::Index(JsonPathIndex::Single(index)) => {
+                let index = index
+                    .as_u64()
+                    .ok_or_else(|| anyhow!("invalid array index {index} in '{path}'"))?;
+                segments.push(PathSegment::Index(index as usize));
+            }
+            _ => anyhow::bail!(
+                "'{path}' does not address a single value - use a plain path such as $.env.FOO or $.permissions.allow[0]"
+            ),
+        }
+    }
+
+    if segments.is_empty() {
+        anyhow::bail!("'{path}' addresses the whole document - name a field such as $.env");
+    }
+    Ok(segments)
+}
+
+/// Display a parsed path back in `$.a.b[0]` form
+pub fn format_path(segments: &[PathSegment]) -> String {
+    let mut path = String::from("$");
+    for segment in segments {
+        match segment {
+            PathSegment::Key(key)
+                if !key.is_empty()
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+            {
+                path.push('.');
+                path.push_str(key);
+            }
+            PathSegment::Key(key) => path.push_str(&format!("['{key}']")),
+            PathSegment::Index(index) => path.push_str(&format!("[{index}]")),
+        }
+    }
+    path
+}
+
+/// Set the value at `segments`, creating missing objects along the way
+///
+/// An index one past the end of an array appends to it, and a missing array
+/// is created for index 0.
+pub fn set_path(root: &mut Value, segments: &[PathSegment], value: Value) -> Result<()> {
+    let (last, parents) = segments.split_last().ok_or_else(|| anyhow!("empty path"))?;
+
+    let mut current = root;
+    for (depth, segment) in parents.iter().enumerate() {
+        current = match segment {
+            PathSegment::Key(key) => {
+                if current.is_null() {
+                    *current = Value::Object(Map::new());
+                }
+                current
+                    .as_object_mut()
+                    .ok_or_else(|| anyhow!("{} is not an object", format_path(&segments[..depth])))?
+                    .entry(key.clone())
+                    .or_insert(Value::Null)
+            }
+            PathSegment::Index(index) => current
+                .as_array_mut()
+                .and_then(|items| items.get_mut(*index))
+                .ok_or_else(|| anyhow!("{} does not exist", format_path(&segments[..=depth])))?,
+        };
+    }
+
+    match last {
+        PathSegment::Key(key) => {
+            if current.is_null() {
+                *current = Value::Object(Map::new());
+            }
+            current
+                .as_object_mut()
+                .ok_or_else(|| anyhow!("{} is not an object", format_path(parents)))?
+                .insert(key.clone(), value);
+        }
+        PathSegment::Index(index) => {
+            if current.is_null() {
+                *current = Value::Array(Vec::new());
+            }
+            let items = current
+                .as_array_mut()
+                .ok_or_else(|| anyhow!("{} is not an array", format_path(parents)))?;
+            match index.cmp(&items.len()) {
+                std::cmp::Ordering::Less => items[*index] = value,
+                std::cmp::Ordering::Equal => items.push(value),
+                std::cmp::Ordering::Greater => anyhow::bail!(
+                    "index {index} is out of bounds for {} (length {})",
+                    format_path(parents),
+                    items.len()
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Remove the value at `segments`, returning it if it existed
+pub fn unset_path(root: &mut Value, segments: &[PathSegment]) -> Option<Value> {
+    let (last, parents) = segments.split_last()?;
+
+    let mut current = root;
+    for segment in parents {
+        current = match segment {
+            PathSegment::Key(key) => current.get_mut(key.as_str())?,
+            PathSegment::Index(index) => current.get_mut(*index)?,
+        };
+    }
+
+    match last {
+        PathSegment::Key(key) => current.as_object_mut()?.remove(key),
+        PathSegment::Index(index) => {
+            let items = current.as_array_mut()?;
+            (*index < items.len()).then(|| items.remove(*index))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("$.env.FOO").unwrap(),
+            vec![
+                PathSegment::Key("env".to_string()),
+                PathSegment::Key("FOO".to_string())
+            ]
+        );
+        assert_eq!(
+            parse_path("permissions.allow[1]").unwrap(),
+            vec![
+                PathSegment::Key("permissions".to_string()),
+                PathSegment::Key("allow".to_string()),
+                PathSegment::Index(1)
+            ]
+        );
+        assert_eq!(
+            parse_path("$['env']['A.B']").unwrap(),
+            vec![
+                PathSegment::Key("env".to_string()),
+                PathSegment::Key("A.B".to_string())
+            ]
+        );
+        assert!(parse_path("$.env.*").is_err());
+        assert!(parse_path("$..allow").is_err());
+        assert!(parse_path("$").is_err());
+        assert_eq!(
+            format_path(&parse_path("$['env']['A.B'].x[0]").unwrap()),
+            "$.env['A.B'].x[0]"
+        );
+    }
+
+    #[test]
+    fn test_set_and_unset_path() {
+        let mut value = json!({"permissions": {"allow": ["Read"]}});
+        set_path(
+            &mut value,
+            &parse_path("$.permissions.deny[0]").unwrap(),
+            json!("WebFetch"),
+        )
+        .unwrap();
+        assert_eq!(
+            unset_path(&mut value, &parse_path("$.permissions.deny").unwrap()),
+            Some(json!(["WebFetch"]))
+        );
+
+        set_path(&mut value, &parse_path("$.env.FOO").unwrap(), json!("bar")).unwrap();
+        set_path(
+            &mut value,
+            &parse_path("$.permissions.allow[1]").unwrap(),
+            json!("Bash(ls)"),
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({"env": {"FOO": "bar"}, "permissions": {"allow": ["Read", "Bash(ls)"]}})
+        );
+
+        assert!(set_path(
+            &mut value,
+            &parse_path("$.permissions.allow[5]").unwrap(),
+            json!(1)
+        )
+        .is_err());
+        assert!(set_path(&mut value, &parse_path("$.env.FOO.x").unwrap(), json!(1)).is_err());
+
+        assert_eq!(
+            unset_path(&mut value, &parse_path("$.permissions.allow[0]").unwrap()),
+            Some(json!("Read"))
+        );
+        assert_eq!(
+            unset_path(&mut value, &parse_path("$.env.BAR").unwrap()),
+            None
+        );
+        assert_eq!(
+            query(&value, "$.permissions.allow[*]").unwrap(),
+            vec![json!("Bash(ls)")]
+        );
+        assert!(query(&value, "$.missing").unwrap().is_empty());
+    }
+}
@@ -1,14 +1,30 @@
 pub mod agents;
+pub mod guard;
 pub mod history;
+pub mod hook_edit;
+pub mod hook_exec;
+pub mod hook_lint;
+pub mod hook_recipes;
+pub mod hook_record;
+pub mod hook_runner;
+pub mod hook_scaffold;
 pub mod hooks;
+pub mod json_path;
+pub mod notify;
 pub mod projects;
 pub mod session;
 pub mod settings;
+pub mod settings_diff;
+pub mod settings_effective;
+pub mod settings_merge;
 pub mod slash_commands;
 
 pub use agents::handle_agents;
+pub use guard::handle_guard;
 pub use history::handle_history;
+pub use hook_exec::handle_hook_exec;
 pub use hooks::handle_hooks;
+pub use notify::handle_notify;
 pub use projects::handle_projects;
 pub use session::handle_session;
 pub use settings::handle_settings;
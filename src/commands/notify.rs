@@ -0,0 +1,318 @@
+use anyhow::{Context, Result};
+use claco::hook_payload::HookPayload;
+use claco::{Config, NotifySink};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use super::hook_exec::shell_quote;
+
+const DEFAULT_WEBHOOK_BODY: &str = r#"{"text": "{{title}}: {{message}}"}"#;
+const DEFAULT_FILE_FORMAT: &str = "[{{timestamp}}] {{title}}: {{message}}";
+
+/// Template values for a notification
+type Vars = BTreeMap<&'static str, String>;
+
+/// Forward a hook notification to every sink configured in claco's config
+///
+/// Meant as a Notification or Stop hook command. Failing sinks are reported
+/// on stderr but never fail the hook, so Claude is not interrupted.
+pub async fn handle_notify(message: Option<String>, test: bool) -> Result<()> {
+    let config = Config::load()?;
+
+    let payload = if test || io::stdin().is_terminal() {
+        None
+    } else {
+        let mut input = Vec::new();
+        io::stdin().read_to_end(&mut input)?;
+        serde_json::from_slice::<HookPayload>(&input).ok()
+    };
+    let vars = notification_vars(payload.as_ref(), message, test)?;
+
+    if config.notify.is_empty() {
+        eprintln!(
+            "warning: no notification sinks configured in {}",
+            Config::config_path()?.display()
+        );
+        return Ok(());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+
+    let mut failed = 0;
+    for sink in &config.notify {
+        match send(sink, &vars, &client).await {
+            Ok(()) if test => println!("Sent test notification via {} sink", sink_label(sink)),
+            Ok(()) => {}
+            Err(e) => {
+                eprintln!("warning: {} sink failed: {e:#}", sink_label(sink));
+                failed += 1;
+            }
+        }
+    }
+
+    if test && failed > 0 {
+        anyhow::bail!("{failed} notification sink(s) failed");
+    }
+    Ok(())
+}
+
+fn sink_label(sink: &NotifySink) -> &'static str {
+    match sink {
+        NotifySink::Webhook { .. } => "webhook",
+        NotifySink::Command { .. } => "command",
+        NotifySink::Bell => "bell",
+        NotifySink::File { .. } => "file",
+    }
+}
+
+/// Build the template values, deriving the message from the payload
+fn notification_vars(
+    payload: Option<&HookPayload>,
+    message: Option<String>,
+    test: bool,
+) -> Result<Vars> {
+    let cwd = match payload {
+        Some(payload) => payload.common().cwd.clone(),
+        None => std::env::current_dir()?.display().to_string(),
+    };
+    let project = Path::new(&cwd)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| cwd.clone());
+
+    let event = match payload {
+        Some(payload) => payload.event().to_string(),
+        None if test => "Test".to_string(),
+        None => String::new(),
+    };
+
+    let message = message.unwrap_or_else(|| match payload {
+        Some(HookPayload::Notification(p)) => p.message.clone(),
+        Some(HookPayload::Stop(_)) => "Claude has finished".to_string(),
+        Some(HookPayload::SubagentStop(_)) => "A subagent has finished".to_string(),
+        Some(HookPayload::SessionEnd(p)) => format!("Session ended ({})", p.reason),
+        Some(payload) => format!("{} hook fired", payload.event()),
+        None if test => "Test notification from claco".to_string(),
+        None => "Claude Code needs your attention".to_string(),
+    });
+
+    Ok(BTreeMap::from([
+        ("title", format!("Claude Code - {project}")),
+        ("message", message),
+        ("event", event),
+        ("project", project),
+        ("cwd", cwd),
+        (
+            "session_id",
+            payload
+                .map(|p| p.common().session_id.clone())
+                .unwrap_or_default(),
+        ),
+        ("timestamp", chrono::Local::now().to_rfc3339()),
+    ]))
+}
+
+/// Replace `{{name}}` placeholders, escaping each value for its context
+///
+/// The template is scanned once, so placeholders inside substituted values
+/// are never expanded. Unknown placeholders are left untouched.
+fn render(template: &str, vars: &Vars, escape: impl Fn(&str) -> String) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after
+            .find("}}")
+            .and_then(|end| Some((end, vars.get(&after[..end])?)));
+        match value {
+            Some((end, value)) => {
+                rendered.push_str(&escape(value));
+                rest = &after[end + 2..];
+            }
+            None => {
+                rendered.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Escape a value for use inside a JSON string literal
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+async fn send(sink: &NotifySink, vars: &Vars, client: &reqwest::Client) -> Result<()> {
+    match sink {
+        NotifySink::Webhook { url, body, headers } => {
+            let body = render(
+                body.as_deref().unwrap_or(DEFAULT_WEBHOOK_BODY),
+                vars,
+                json_escape,
+            );
+            serde_json::from_str::<serde_json::Value>(&body)
+                .context("webhook body template did not render valid JSON")?;
+
+            let mut request = client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(body);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            let response = request
+                .send()
+                .await
+                // reqwest's message already includes the underlying cause
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+            if !response.status().is_success() {
+                anyhow::bail!("{url} responded with {}", response.status());
+            }
+        }
+        NotifySink::Command { command } => {
+            let command = render(command, vars, shell_quote);
+            // Keep stdout clear: Claude Code reads it as hook output
+            let status = Command::new("sh")
+                .arg("-c")
+                .arg(&command)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .status()
+                .with_context(|| format!("Failed to run: {command}"))?;
+            if !status.success() {
+                anyhow::bail!("'{command}' exited with {status}");
+            }
+        }
+        NotifySink::Bell => {
+            // Hooks have no terminal on stdout, so ring the controlling one
+            match OpenOptions::new().write(true).open("/dev/tty") {
+                Ok(mut tty) => tty.write_all(b"\x07")?,
+                Err(_) => io::stderr().write_all(b"\x07")?,
+            }
+        }
+        NotifySink::File { path, format } => {
+            let line = render(
+                format.as_deref().unwrap_or(DEFAULT_FILE_FORMAT),
+                vars,
+                str::to_string,
+            );
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            writeln!(file, "{line}")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+    use std::net::TcpListener;
+
+    fn vars() -> Vars {
+        let payload: HookPayload = serde_json::from_value(serde_json::json!({
+            "hook_event_name": "Notification",
+            "session_id": "abc",
+            "transcript_path": "/tmp/abc.jsonl",
+            "cwd": "/work/my-app",
+            "message": "Claude needs your \"permission\""
+        }))
+        .unwrap();
+        notification_vars(Some(&payload), None, false).unwrap()
+    }
+
+    #[test]
+    fn test_render() {
+        let vars = vars();
+        assert_eq!(vars["title"], "Claude Code - my-app");
+        assert_eq!(
+            render("{{event}}: {{message}} {{other}}", &vars, str::to_string),
+            "Notification: Claude needs your \"permission\" {{other}}"
+        );
+        assert_eq!(
+            render(DEFAULT_WEBHOOK_BODY, &vars, json_escape),
+            r#"{"text": "Claude Code - my-app: Claude needs your \"permission\""}"#
+        );
+        assert_eq!(
+            render("notify-send {{message}}", &vars, shell_quote),
+            r#"notify-send 'Claude needs your "permission"'"#
+        );
+
+        // Placeholders inside values are not expanded again
+        let mut vars = vars;
+        vars.insert("message", "see {{project}} in {{cwd}}".to_string());
+        vars.insert("project", "x' $(id) '".to_string());
+        assert_eq!(
+            render("notify-send {{title}} {{message}}", &vars, shell_quote),
+            "notify-send 'Claude Code - my-app' 'see {{project}} in {{cwd}}'"
+        );
+        assert_eq!(
+            render("{{{{message}}}} {{project", &vars, str::to_string),
+            "{{see {{project}} in {{cwd}}}} {{project"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink() {
+        // Minimal HTTP stand-in that captures one request body
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = io::BufReader::new(stream.try_clone().unwrap());
+            let mut length = 0;
+            let mut token = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_string();
+                if line.is_empty() {
+                    break;
+                }
+                let lower = line.to_ascii_lowercase();
+                if let Some(value) = lower.strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if let Some(value) = lower.strip_prefix("x-token:") {
+                    token = value.trim().to_string();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            (&stream)
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            (token, String::from_utf8(body).unwrap())
+        });
+
+        let sink = NotifySink::Webhook {
+            url,
+            body: Some(r#"{"content": "{{message}}", "event": "{{event}}"}"#.to_string()),
+            headers: BTreeMap::from([("X-Token".to_string(), "secret".to_string())]),
+        };
+        send(&sink, &vars(), &reqwest::Client::new()).await.unwrap();
+
+        let (token, body) = server.join().unwrap();
+        assert_eq!(token, "secret");
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["content"], "Claude needs your \"permission\"");
+        assert_eq!(body["event"], "Notification");
+    }
+}
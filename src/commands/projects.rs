@@ -1,54 +1,33 @@
 use anyhow::Result;
-use claco::{claude_home, desanitize_project_path, SessionEntry};
-use std::fs;
-use std::io::{BufRead, BufReader};
+use claco::desanitize_project_path;
+use claco::transcript::{project_dirs, projects_dir, session_files, session_overview};
 
 /// List all Claude Code projects with their sessions
 ///
 /// Reads the ~/.claude/projects directory and displays:
 /// - Project paths (desanitized from directory names)
-/// - Associated session IDs for each project
+/// - Associated sessions for each project with their titles
 /// - Attempts to extract the actual cwd from session files
 pub fn handle_projects() -> Result<()> {
-    let projects_dir = claude_home()?.join("projects");
-
-    if !projects_dir.exists() {
+    if !projects_dir()?.exists() {
         println!("No Claude projects directory found");
         return Ok(());
     }
 
-    for entry in fs::read_dir(&projects_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if !path.is_dir() {
-            continue;
-        }
-
+    for path in project_dirs()? {
         // Collect sessions and try to get the actual cwd from any JSONL file
         let mut sessions = Vec::new();
         let mut actual_cwd = None;
 
-        for session_entry in fs::read_dir(&path)? {
-            let session_entry = session_entry?;
-            let session_path = session_entry.path();
-
-            if session_path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                if let Some(session_id) = session_path.file_stem() {
-                    sessions.push(session_id.to_string_lossy().into_owned());
-                }
+        for session_path in session_files(&path)? {
+            // Title comes from a summary entry or the first prompt
+            let overview = session_overview(&session_path).unwrap_or_default();
+            if let Some(session_id) = session_path.file_stem() {
+                sessions.push((session_id.to_string_lossy().into_owned(), overview.title));
+            }
 
-                // Try to read the actual cwd from the first line of this JSONL file
-                if actual_cwd.is_none() {
-                    if let Ok(file) = fs::File::open(&session_path) {
-                        let reader = BufReader::new(file);
-                        if let Some(Ok(first_line)) = reader.lines().next() {
-                            if let Ok(entry) = serde_json::from_str::<SessionEntry>(&first_line) {
-                                actual_cwd = entry.cwd;
-                            }
-                        }
-                    }
-                }
+            if actual_cwd.is_none() {
+                actual_cwd = overview.cwd;
             }
         }
 
@@ -69,7 +48,13 @@ pub fn handle_projects() -> Result<()> {
         };
 
         println!("Project: {project_path}");
-        println!("  Sessions: {sessions:?}");
+        println!("  Sessions:");
+        for (session_id, title) in &sessions {
+            match title {
+                Some(title) => println!("    {session_id}  {title}"),
+                None => println!("    {session_id}"),
+            }
+        }
         println!();
     }
 
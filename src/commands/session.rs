@@ -1,137 +1,405 @@
 use anyhow::Result;
-use claco::{claude_home, SessionEntry};
-use std::fs;
-use std::io::{BufRead, BufReader};
+use claco::transcript::{
+    find_project_dir_for_cwd, find_session_file, most_recent_session_file, project_dirs,
+    read_transcript, session_files, truncate_title, ContextSegment, ToolErrorKind, Transcript,
+};
+use claco::{EntryKind, SessionAction};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 use super::format_timestamp_local;
 
+/// Handle the session command and its subcommands
+///
+/// Without a subcommand, displays information about a single session.
+pub fn handle_session(action: Option<SessionAction>, session_id: Option<String>) -> Result<()> {
+    match action {
+        Some(SessionAction::Validate { session_id, all }) => {
+            handle_session_validate(session_id, all)
+        }
+        Some(SessionAction::Errors {
+            session_id,
+            project,
+        }) => handle_session_errors(session_id, project),
+        None => handle_session_info(session_id),
+    }
+}
+
+/// Resolve a session ID to its transcript file, defaulting to the most recent session
+///
+/// Prints a message and returns `None` when no matching session exists.
+fn resolve_session_file(session_id: Option<String>) -> Result<Option<PathBuf>> {
+    match session_id {
+        Some(id) => {
+            let session_file = find_session_file(&id)?;
+            if session_file.is_none() {
+                println!("Session not found: {id}");
+            }
+            Ok(session_file)
+        }
+        None => {
+            let session_file = most_recent_session_file()?;
+            match &session_file {
+                Some(path) => {
+                    if let Some(id) = path.file_stem() {
+                        println!("Using most recent session: {}", id.to_string_lossy());
+                    }
+                }
+                None => println!("No sessions found"),
+            }
+            Ok(session_file)
+        }
+    }
+}
+
 /// Display information about a specific Claude Code session
 ///
-/// Shows details about a session including the first user message and timestamp.
+/// Shows details about a session including the first user message and timestamp,
+/// followed by compaction points and context growth between them.
 /// If no session ID is provided, displays the most recent session.
 ///
 /// # Arguments
 /// * `session_id` - Optional specific session ID to display
-pub fn handle_session(session_id: Option<String>) -> Result<()> {
-    let projects_dir = claude_home()?.join("projects");
+fn handle_session_info(session_id: Option<String>) -> Result<()> {
+    let session_file = match resolve_session_file(session_id)? {
+        Some(path) => path,
+        None => return Ok(()),
+    };
 
-    if !projects_dir.exists() {
-        println!("No Claude projects directory found");
-        return Ok(());
+    let transcript = read_transcript(&session_file)?;
+    transcript.warn_failures();
+
+    println!("Session ID: {}", transcript.session_id());
+
+    if let Some(title) = transcript.title() {
+        println!("Title: {title}");
     }
 
-    // If no session_id provided, find the most recent session
-    let target_session_id = if let Some(id) = session_id {
-        id
-    } else {
-        // Find the most recent JSONL file across all projects
-        let mut most_recent_session = None;
-        let mut most_recent_time = None;
+    let mut first_user_message = None;
+    let mut first_timestamp = None;
+    let mut project_cwd = None;
 
-        for project_entry in fs::read_dir(&projects_dir)? {
-            let project_entry = project_entry?;
-            let project_path = project_entry.path();
+    for line in &transcript.entries {
+        let entry = &line.entry;
 
-            if !project_path.is_dir() {
-                continue;
-            }
+        if project_cwd.is_none() {
+            project_cwd = entry.cwd.clone();
+        }
 
-            for session_entry in fs::read_dir(&project_path)? {
-                let session_entry = session_entry?;
-                let session_path = session_entry.path();
-
-                if session_path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                    if let Ok(metadata) = session_entry.metadata() {
-                        if let Ok(modified) = metadata.modified() {
-                            if most_recent_time.is_none() || modified > most_recent_time.unwrap() {
-                                most_recent_time = Some(modified);
-                                most_recent_session = session_path
-                                    .file_stem()
-                                    .map(|s| s.to_string_lossy().to_string());
-                            }
-                        }
-                    }
-                }
-            }
+        if first_timestamp.is_none() {
+            first_timestamp = entry.timestamp.clone();
         }
 
-        match most_recent_session {
-            Some(id) => {
-                println!("Using most recent session: {id}");
-                id
+        if entry.kind() == EntryKind::User
+            && entry.user_type.as_deref() == Some("external")
+            && first_user_message.is_none()
+        {
+            if let Some(ref message) = entry.message {
+                first_user_message = Some(message.content.clone());
             }
-            None => {
-                println!("No sessions found");
-                return Ok(());
+        }
+    }
+
+    if let Some(cwd) = project_cwd {
+        println!("Project: {cwd}");
+    }
+
+    if let Some(timestamp) = first_timestamp {
+        println!("Started: {}", format_timestamp_local(&timestamp));
+    }
+
+    if let Some(message) = first_user_message {
+        println!("First user message: {message}");
+    }
+
+    print_compactions(&transcript);
+
+    Ok(())
+}
+
+/// Print compaction points and the context growth between them
+fn print_compactions(transcript: &Transcript) {
+    let compactions = transcript.compactions();
+    let segments = transcript.context_segments();
+
+    if compactions.is_empty() {
+        if let Some(segment) = segments.first().filter(|s| s.turns > 0) {
+            println!("Context: {}", describe_segment(segment));
+        }
+        return;
+    }
+
+    println!("Compactions: {}", compactions.len());
+    for (i, segment) in segments.iter().enumerate() {
+        println!("  Segment {}: {}", i + 1, describe_segment(segment));
+
+        if let Some(compaction) = compactions.get(i) {
+            let when = compaction
+                .timestamp
+                .as_deref()
+                .map(format_timestamp_local)
+                .unwrap_or_else(|| "unknown time".to_string());
+            let trigger = compaction.trigger.as_deref().unwrap_or("unknown");
+
+            let mut line = format!(
+                "  -- {trigger} compaction at line {} ({when})",
+                compaction.line
+            );
+            if let Some(pre_tokens) = compaction.pre_tokens {
+                line.push_str(&format!(", {pre_tokens} tokens before"));
             }
+            println!("{line}");
+        }
+    }
+}
+
+fn describe_segment(segment: &ContextSegment) -> String {
+    match (
+        segment.start_tokens,
+        segment.end_tokens,
+        segment.peak_tokens,
+    ) {
+        (Some(start), Some(end), Some(peak)) => format!(
+            "{start} -> {end} tokens (peak {peak}) over {} request(s)",
+            segment.turns
+        ),
+        _ => "no usage data".to_string(),
+    }
+}
+
+/// Validate one or all session transcripts
+///
+/// Classifies every line by entry type and reports lines that fail to parse,
+/// returning an error when any failures were found.
+fn handle_session_validate(session_id: Option<String>, all: bool) -> Result<()> {
+    let session_files_to_check = if all {
+        let mut files = Vec::new();
+        for project_path in project_dirs()? {
+            files.extend(session_files(&project_path)?);
+        }
+        if files.is_empty() {
+            println!("No sessions found");
+            return Ok(());
+        }
+        files
+    } else {
+        match resolve_session_file(session_id)? {
+            Some(path) => vec![path],
+            None => return Ok(()),
         }
     };
 
-    // Search all project directories for the session
-    for project_entry in fs::read_dir(&projects_dir)? {
-        let project_entry = project_entry?;
-        let project_path = project_entry.path();
+    let mut total_failures = 0;
+    let mut sessions_with_failures = 0;
+
+    for session_file in &session_files_to_check {
+        let transcript = read_transcript(session_file)?;
 
-        if !project_path.is_dir() {
+        // In --all mode only report sessions that need attention
+        let unknown_count = transcript
+            .entries
+            .iter()
+            .filter(|line| matches!(line.entry.kind(), EntryKind::Unknown(_)))
+            .count();
+        if all && transcript.failures.is_empty() && unknown_count == 0 {
             continue;
         }
 
-        let session_file = project_path.join(format!("{target_session_id}.jsonl"));
+        print_validation_report(&transcript);
 
-        if session_file.exists() {
-            // Found the session
-            println!("Session ID: {target_session_id}");
+        if !transcript.failures.is_empty() {
+            total_failures += transcript.failures.len();
+            sessions_with_failures += 1;
+        }
+    }
 
-            // Read first user message and get timestamp
-            let file = fs::File::open(&session_file)?;
-            let reader = BufReader::new(file);
+    println!(
+        "Validated {} session(s): {} with parse failures",
+        session_files_to_check.len(),
+        sessions_with_failures
+    );
 
-            let mut first_user_message = None;
-            let mut first_timestamp = None;
-            let mut project_cwd = None;
+    if total_failures > 0 {
+        anyhow::bail!("{total_failures} line(s) failed to parse");
+    }
 
-            for line in reader.lines() {
-                let line = line?;
-                if line.trim().is_empty() {
-                    continue;
-                }
+    Ok(())
+}
 
-                let entry: SessionEntry = serde_json::from_str(&line)?;
+fn print_validation_report(transcript: &Transcript) {
+    println!(
+        "Session: {} ({})",
+        transcript.session_id(),
+        transcript.path.display()
+    );
 
-                if project_cwd.is_none() {
-                    project_cwd = entry.cwd.clone();
-                }
+    // Count entries per kind, keeping unknown types apart for reporting
+    let mut counts: BTreeMap<EntryKind, usize> = BTreeMap::new();
+    let mut unknown_types: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for line in &transcript.entries {
+        let kind = line.entry.kind();
+        if let EntryKind::Unknown(ref type_name) = kind {
+            unknown_types
+                .entry(type_name.clone())
+                .or_default()
+                .push(line.line);
+        }
+        *counts
+            .entry(match kind {
+                EntryKind::Unknown(_) => EntryKind::Unknown(String::new()),
+                other => other,
+            })
+            .or_insert(0) += 1;
+    }
 
-                if first_timestamp.is_none() {
-                    first_timestamp = entry.timestamp.clone();
-                }
+    let summary: Vec<String> = counts
+        .iter()
+        .map(|(kind, count)| format!("{}={count}", kind.label()))
+        .collect();
+    println!("  Entries: {}", summary.join(" "));
 
-                if entry.message_type == "user"
-                    && entry.user_type.as_deref() == Some("external")
-                    && first_user_message.is_none()
-                {
-                    if let Some(ref message) = entry.message {
-                        first_user_message = Some(message.content.clone());
-                    }
-                }
-            }
+    for (type_name, lines) in &unknown_types {
+        let type_label = if type_name.is_empty() {
+            "<missing>"
+        } else {
+            type_name.as_str()
+        };
+        println!(
+            "  Unknown type '{}' on {} line(s), first at line {}",
+            type_label,
+            lines.len(),
+            lines[0]
+        );
+    }
+
+    for failure in &transcript.failures {
+        println!("  line {}: {}", failure.line, failure.error);
+    }
 
-            if let Some(cwd) = project_cwd {
-                println!("Project: {cwd}");
+    println!();
+}
+
+/// List tool errors, user rejections and interrupted requests
+///
+/// Inspects a single session, or all sessions of the current project with
+/// `--project`, and summarizes failures that repeat across sessions.
+fn handle_session_errors(session_id: Option<String>, project: bool) -> Result<()> {
+    let session_files_to_check = if project {
+        let cwd = std::env::current_dir()?;
+        let cwd_str = cwd.to_string_lossy();
+        match find_project_dir_for_cwd(&cwd_str)? {
+            Some(project_path) => session_files(&project_path)?,
+            None => {
+                println!("No Claude project found for current directory: {cwd_str}");
+                return Ok(());
             }
+        }
+    } else {
+        match resolve_session_file(session_id)? {
+            Some(path) => vec![path],
+            None => return Ok(()),
+        }
+    };
+
+    let mut counts_by_tool: BTreeMap<String, BTreeMap<ToolErrorKind, usize>> = BTreeMap::new();
+    let mut repeated: BTreeMap<(String, String), usize> = BTreeMap::new();
+    let mut total = 0;
+
+    for session_file in &session_files_to_check {
+        let transcript = read_transcript(session_file)?;
+        transcript.warn_failures();
+
+        let errors = transcript.tool_errors();
+        if errors.is_empty() {
+            continue;
+        }
+
+        match transcript.title() {
+            Some(title) => println!("Session: {}  {}", transcript.session_id(), title),
+            None => println!("Session: {}", transcript.session_id()),
+        }
 
-            if let Some(timestamp) = first_timestamp {
-                println!("Started: {}", format_timestamp_local(&timestamp));
+        for error in &errors {
+            let tool = error.tool_name.as_deref().unwrap_or("(request)");
+            let input = error
+                .tool_input
+                .as_ref()
+                .map(summarize_tool_input)
+                .unwrap_or_default();
+            let when = error
+                .timestamp
+                .as_deref()
+                .map(format_timestamp_local)
+                .unwrap_or_else(|| format!("line {}", error.line));
+
+            if input.is_empty() {
+                println!("  {} [{}] {}", when, error.kind.label(), tool);
+            } else {
+                println!("  {} [{}] {}: {}", when, error.kind.label(), tool, input);
+            }
+            if !error.message.is_empty() {
+                println!("    {}", truncate_title(&error.message, 120));
             }
 
-            if let Some(message) = first_user_message {
-                println!("First user message: {message}");
+            *counts_by_tool
+                .entry(tool.to_string())
+                .or_default()
+                .entry(error.kind)
+                .or_insert(0) += 1;
+            if error.kind == ToolErrorKind::Error {
+                *repeated.entry((tool.to_string(), input)).or_insert(0) += 1;
             }
+            total += 1;
+        }
+        println!();
+    }
 
-            return Ok(());
+    if total == 0 {
+        println!("No tool errors found");
+        return Ok(());
+    }
+
+    if session_files_to_check.len() > 1 {
+        println!(
+            "Summary across {} session(s):",
+            session_files_to_check.len()
+        );
+        for (tool, counts) in &counts_by_tool {
+            let parts: Vec<String> = counts
+                .iter()
+                .map(|(kind, count)| format!("{count} {}", kind.label()))
+                .collect();
+            println!("  {}: {}", tool, parts.join(", "));
+        }
+
+        let mut repeated: Vec<_> = repeated.into_iter().filter(|(_, n)| *n > 1).collect();
+        if !repeated.is_empty() {
+            repeated.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+            println!();
+            println!("Repeated failures:");
+            for ((tool, input), count) in repeated {
+                println!("  {count}x {tool}: {input}");
+            }
         }
     }
 
-    println!("Session not found: {target_session_id}");
     Ok(())
 }
+
+/// Short, single-line description of a tool input
+fn summarize_tool_input(input: &Value) -> String {
+    let summary = [
+        "command",
+        "file_path",
+        "path",
+        "pattern",
+        "url",
+        "description",
+    ]
+    .iter()
+    .find_map(|key| input.get(key).and_then(|v| v.as_str()))
+    .map(String::from)
+    .unwrap_or_else(|| input.to_string());
+    truncate_title(&summary, 100)
+}
@@ -1,13 +1,17 @@
 use anyhow::{anyhow, Context, Result};
-use claco::claude::{
-    load_settings, project_local_settings_path, project_settings_path, save_settings,
-    user_settings_path, Settings,
-};
-use claco::cli::{Scope, SettingsSubcommand};
-use std::collections::HashMap;
+use chrono::Local;
+use claco::claude::{load_settings, save_settings_backed_up, write_settings_file, Settings};
+use claco::cli::{ArrayStrategy, Scope, SettingsSubcommand};
+use claco::settings_backup::{backup_dir, list_snapshots, BackupTarget, Snapshot};
+use claco::Config;
 use std::fs;
 use std::path::PathBuf;
 
+use super::json_path::{format_path, parse_path, query, set_path, unset_path};
+use super::settings_diff::{diff_summary, diff_values, use_color, DiffEntry};
+use super::settings_effective::show_effective_settings;
+use super::settings_merge::{merge_settings, Conflict};
+
 /// Format JSON parsing errors with line/column information
 fn format_json_error(err: &serde_json::Error, content: &str) -> String {
     let line_num = err.line();
@@ -55,21 +59,286 @@ pub async fn handle_settings(cmd: SettingsSubcommand) -> Result<()> {
             source,
             scope,
             overwrite,
-        } => apply_settings(&source, scope, overwrite).await,
+            strategy,
+            dry_run,
+        } => apply_settings(&source, scope, overwrite, strategy, dry_run).await,
+        SettingsSubcommand::Show { scope, effective } => {
+            if effective {
+                show_effective_settings()
+            } else {
+                settings_show(scope.unwrap_or(Scope::Project))
+            }
+        }
+        SettingsSubcommand::Diff { a, b } => settings_diff(&a, &b).await,
+        SettingsSubcommand::Get { path, scope } => settings_get(&path, scope),
+        SettingsSubcommand::Set { path, value, scope } => settings_set(&path, &value, scope),
+        SettingsSubcommand::Unset { path, scope } => settings_unset(&path, scope),
+        SettingsSubcommand::History { scope } => settings_history(scope),
+        SettingsSubcommand::Restore { snapshot, scope } => settings_restore(&snapshot, scope),
+    }
+}
+
+/// Print a scope's settings file as loaded
+fn settings_show(scope: Scope) -> Result<()> {
+    let settings_path = scope.settings_path()?;
+    if !settings_path.exists() {
+        println!(
+            "No {} settings file at {}",
+            scope.label(),
+            settings_path.display()
+        );
+        return Ok(());
+    }
+
+    println!("Settings file: {}", settings_path.display());
+    let settings = load_settings(&settings_path)?;
+    println!("{}", serde_json::to_string_pretty(&settings)?);
+    Ok(())
+}
+
+/// Load one side of a diff: a scope name, a local file or a GitHub URL
+///
+/// Scope names win over files with the same name; use `./user` for a file.
+async fn load_diff_side(side: &str) -> Result<(String, Settings)> {
+    if let Ok(scope) = <Scope as clap::ValueEnum>::from_str(side, false) {
+        let settings_path = scope.settings_path()?;
+        let label = format!("{} ({})", scope.label(), settings_path.display());
+        return Ok((label, load_settings(&settings_path)?));
+    }
+    Ok((side.to_string(), load_source_settings(side).await?))
+}
+
+/// Print a structural diff from `a` to `b`
+async fn settings_diff(a: &str, b: &str) -> Result<()> {
+    let (a_label, a_settings) = load_diff_side(a).await?;
+    let (b_label, b_settings) = load_diff_side(b).await?;
+
+    println!("--- {a_label}");
+    println!("+++ {b_label}");
+
+    let entries = diff_values(
+        &serde_json::to_value(a_settings)?,
+        &serde_json::to_value(b_settings)?,
+    );
+    if entries.is_empty() {
+        println!("No differences");
+        return Ok(());
+    }
+
+    let color = use_color();
+    for entry in &entries {
+        println!("{}", entry.colored(color));
+    }
+    println!();
+    println!("{}", diff_summary(&entries));
+    Ok(())
+}
+
+/// Print the values matching a JSONPath in a scope's settings
+fn settings_get(path: &str, scope: Scope) -> Result<()> {
+    let settings = serde_json::to_value(load_settings(&scope.settings_path()?)?)?;
+    let mut matches = query(&settings, path)?;
+
+    match matches.len() {
+        0 => anyhow::bail!("no value at {path} in {} settings", scope.label()),
+        1 => println!("{}", serde_json::to_string_pretty(&matches.remove(0))?),
+        _ => println!("{}", serde_json::to_string_pretty(&matches)?),
+    }
+    Ok(())
+}
+
+/// Set a single value in a scope's settings, keeping every other field
+fn settings_set(path: &str, value: &str, scope: Scope) -> Result<()> {
+    let value: serde_json::Value = serde_json::from_str(value).map_err(|e| {
+        anyhow!("invalid JSON value '{value}': {e} - strings need quotes, e.g. '\"{value}\"'")
+    })?;
+    let segments = parse_path(path)?;
+
+    let settings_path = scope.settings_path()?;
+    let mut settings = serde_json::to_value(load_settings(&settings_path)?)?;
+    set_path(&mut settings, &segments, value)?;
+    let settings: Settings = serde_json::from_value(settings)
+        .with_context(|| format!("Setting {path} would make the settings invalid"))?;
+    save_settings_backed_up(&settings_path, &settings)?;
+
+    println!(
+        "Set {} in {} settings ({})",
+        format_path(&segments),
+        scope.label(),
+        settings_path.display()
+    );
+    Ok(())
+}
+
+/// Remove a single value from a scope's settings
+fn settings_unset(path: &str, scope: Scope) -> Result<()> {
+    let segments = parse_path(path)?;
+
+    let settings_path = scope.settings_path()?;
+    let mut settings = serde_json::to_value(load_settings(&settings_path)?)?;
+    if unset_path(&mut settings, &segments).is_none() {
+        println!(
+            "{} is not set in {} settings",
+            format_path(&segments),
+            scope.label()
+        );
+        return Ok(());
+    }
+    let settings: Settings = serde_json::from_value(settings)
+        .with_context(|| format!("Removing {path} would make the settings invalid"))?;
+    save_settings_backed_up(&settings_path, &settings)?;
+
+    println!(
+        "Removed {} from {} settings ({})",
+        format_path(&segments),
+        scope.label(),
+        settings_path.display()
+    );
+    Ok(())
+}
+
+/// Backup directory and snapshots for a scope's settings, newest first
+fn scope_snapshots(scope: &Scope) -> Result<(PathBuf, Vec<Snapshot>)> {
+    let settings_path = scope.settings_path()?;
+    let dir = backup_dir(&Config::load()?.data_dir, &settings_path)?;
+    Ok((settings_path, list_snapshots(&dir)?))
+}
+
+/// List the backups of a scope's settings
+fn settings_history(scope: Scope) -> Result<()> {
+    let (settings_path, snapshots) = scope_snapshots(&scope)?;
+    if snapshots.is_empty() {
+        println!(
+            "No backups of {} settings ({})",
+            scope.label(),
+            settings_path.display()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Backups of {} settings ({}), newest first:",
+        scope.label(),
+        settings_path.display()
+    );
+    for snapshot in &snapshots {
+        println!(
+            "  {}  {}  {} bytes",
+            snapshot.id,
+            snapshot
+                .created
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S"),
+            snapshot.size
+        );
+    }
+    println!();
+    println!(
+        "Restore one with: claco settings restore <id> --scope {}",
+        scope.label()
+    );
+    Ok(())
+}
+
+/// Replace a scope's settings with a backup
+///
+/// The settings being replaced are backed up first, so a restore can itself
+/// be undone.
+fn settings_restore(snapshot: &str, scope: Scope) -> Result<()> {
+    let (settings_path, snapshots) = scope_snapshots(&scope)?;
+    let found = if snapshot == "latest" {
+        snapshots.first()
+    } else {
+        snapshots.iter().find(|s| s.id == snapshot)
+    };
+    let Some(found) = found else {
+        anyhow::bail!(
+            "no backup '{snapshot}' of {} settings - run `claco settings history --scope {}` to list them",
+            scope.label(),
+            scope.label()
+        );
+    };
+
+    // Restore the exact bytes that were backed up, not a re-serialized copy
+    let content = fs::read(&found.path)
+        .with_context(|| format!("Failed to read backup {}", found.path.display()))?;
+    let current = match fs::read(&settings_path) {
+        Ok(current) => Some(current),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", settings_path.display()))
+        }
+    };
+
+    // Diff only when both sides parse; a broken settings file is exactly what
+    // restore is for
+    let current_value = match &current {
+        Some(current) => serde_json::from_slice::<serde_json::Value>(current).ok(),
+        None => Some(serde_json::json!({})),
+    };
+    let restored_value = serde_json::from_slice::<serde_json::Value>(&content).ok();
+    match (current_value, restored_value) {
+        (Some(current), Some(restored)) => {
+            let entries = diff_values(&current, &restored);
+            let color = use_color();
+            for entry in &entries {
+                println!("{}", entry.colored(color));
+            }
+            if !entries.is_empty() {
+                println!();
+            }
+        }
+        (None, _) => println!(
+            "The current {} settings are not valid JSON; replacing them without a diff",
+            scope.label()
+        ),
+        (_, None) => eprintln!("warning: backup {} is not valid JSON", found.id),
+    }
+
+    // Back up the current bytes first so the restore can itself be undone
+    if let Some(target) = BackupTarget::configured() {
+        if let Err(e) = target.snapshot(&settings_path) {
+            eprintln!(
+                "warning: failed to back up {} before restoring: {e:#}",
+                settings_path.display()
+            );
+        }
     }
+    write_settings_file(&settings_path, &content)?;
+
+    println!(
+        "Restored {} settings ({}) from backup {}",
+        scope.label(),
+        settings_path.display(),
+        found.id
+    );
+    Ok(())
 }
 
 /// Apply settings from a source file or URL
-async fn apply_settings(source: &str, scope: Scope, overwrite: bool) -> Result<()> {
+async fn apply_settings(
+    source: &str,
+    scope: Scope,
+    overwrite: bool,
+    strategy: ArrayStrategy,
+    dry_run: bool,
+) -> Result<()> {
     // Get the source settings
     let source_settings = load_source_settings(source).await?;
 
     // Get the target settings path
-    let target_path = match scope {
-        Scope::User => user_settings_path()?,
-        Scope::Project => project_settings_path(),
-        Scope::ProjectLocal => project_local_settings_path(),
-    };
+    let target_path = scope.settings_path()?;
+
+    if dry_run {
+        let (conflicts, entries) = preview_apply(&target_path, source_settings, strategy)?;
+        println!(
+            "Dry run: applying {source} to {} settings ({})",
+            scope.label(),
+            target_path.display()
+        );
+        print_apply_preview(&conflicts, &entries, overwrite);
+        return Ok(());
+    }
 
     // Load existing settings
     // NOTE: There is a race condition window between loading and saving settings.
@@ -78,24 +347,75 @@ async fn apply_settings(source: &str, scope: Scope, overwrite: bool) -> Result<(
     // is acceptable since settings modifications are typically infrequent.
     let mut target_settings = load_settings(&target_path)?;
 
-    // Merge settings
-    merge_settings(&mut target_settings, source_settings, overwrite)?;
+    // Merge settings, aborting before saving if values conflict
+    let conflicts = merge_settings(&mut target_settings, source_settings, overwrite, strategy);
+    if !overwrite && !conflicts.is_empty() {
+        let paths: Vec<String> = conflicts.iter().map(|c| format!("  {c}")).collect();
+        return Err(anyhow!(
+            "Conflicts detected at the following paths:\n{}\n\nUse --overwrite to replace existing settings",
+            paths.join("\n")
+        ));
+    }
 
     // Save the merged settings
-    save_settings(&target_path, &target_settings)?;
+    save_settings_backed_up(&target_path, &target_settings)?;
 
-    println!(
-        "Successfully applied settings to {} scope",
-        match scope {
-            Scope::User => "user",
-            Scope::Project => "project",
-            Scope::ProjectLocal => "project.local",
-        }
-    );
+    println!("Successfully applied settings to {} scope", scope.label());
 
     Ok(())
 }
 
+/// Work out what `settings apply` would change without writing anything
+///
+/// Returns the conflicting values and the diff of the target settings. As
+/// conflicts are reported either way, the diff shows what `--overwrite`
+/// would do.
+fn preview_apply(
+    target_path: &PathBuf,
+    source_settings: Settings,
+    strategy: ArrayStrategy,
+) -> Result<(Vec<Conflict>, Vec<DiffEntry>)> {
+    let target = load_settings(target_path)?;
+    let mut merged = target.clone();
+    let conflicts = merge_settings(&mut merged, source_settings, true, strategy);
+    let entries = diff_values(
+        &serde_json::to_value(&target)?,
+        &serde_json::to_value(&merged)?,
+    );
+    Ok((conflicts, entries))
+}
+
+/// Print a `settings apply --dry-run` preview
+///
+/// When the source conflicts with the target and `--overwrite` is not set,
+/// apply would abort, so the preview shows what `--overwrite` would replace.
+fn print_apply_preview(conflicts: &[Conflict], entries: &[DiffEntry], overwrite: bool) {
+    if !conflicts.is_empty() {
+        println!();
+        println!("Conflicts (set to a different value in the target):");
+        for conflict in conflicts {
+            println!("  {conflict}");
+        }
+        if !overwrite {
+            println!();
+            println!("Without --overwrite, apply would stop here. With --overwrite it would:");
+        }
+    }
+
+    println!();
+    if entries.is_empty() {
+        println!("No changes");
+    } else {
+        let color = use_color();
+        for entry in entries {
+            println!("{}", entry.colored(color));
+        }
+        println!();
+        println!("{}", diff_summary(entries));
+    }
+    println!("Dry run: no files were changed");
+}
+
 /// Load settings from a source (file path or GitHub URL)
 async fn load_source_settings(source: &str) -> Result<Settings> {
     if source.starts_with("https://github.com/") {
@@ -214,73 +534,6 @@ fn load_from_local_file(path: &str) -> Result<Settings> {
     }
 }
 
-/// Merge source settings into target settings
-fn merge_settings(target: &mut Settings, source: Settings, overwrite: bool) -> Result<()> {
-    // Check for conflicts if not overwriting
-    if !overwrite {
-        check_for_conflicts(target, &source)?;
-    }
-
-    // Merge hooks
-    if let Some(source_hooks) = source.hooks {
-        if target.hooks.is_none() {
-            target.hooks = Some(HashMap::new());
-        }
-
-        let target_hooks = target.hooks.as_mut().unwrap();
-
-        for (event, matchers) in source_hooks {
-            if overwrite {
-                target_hooks.insert(event, matchers);
-            } else {
-                // Append matchers for existing events
-                target_hooks.entry(event).or_default().extend(matchers);
-            }
-        }
-    }
-
-    // Merge other fields
-    for (key, value) in source.other {
-        if overwrite || !target.other.contains_key(&key) {
-            target.other.insert(key, value);
-        }
-    }
-
-    Ok(())
-}
-
-/// Check for conflicts between target and source settings
-fn check_for_conflicts(target: &Settings, source: &Settings) -> Result<()> {
-    let mut conflicts = Vec::new();
-
-    // Check for conflicting hooks
-    if let (Some(target_hooks), Some(source_hooks)) = (&target.hooks, &source.hooks) {
-        for event in source_hooks.keys() {
-            if target_hooks.contains_key(event) {
-                conflicts.push(format!("$.hooks.{event}"));
-            }
-        }
-    }
-
-    // Check for conflicting other fields
-    for key in source.other.keys() {
-        if target.other.contains_key(key) {
-            // Build JSONPath for the key
-            let jsonpath = format!("$.{key}");
-            conflicts.push(jsonpath);
-        }
-    }
-
-    if !conflicts.is_empty() {
-        return Err(anyhow!(
-            "Conflicts detected at the following paths:\n{}\n\nUse --overwrite to replace existing settings",
-            conflicts.join("\n")
-        ));
-    }
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,4 +568,52 @@ mod tests {
             "https://raw.githubusercontent.com/owner/repo/main/file.json?ref=feature"
         );
     }
+
+    #[test]
+    fn test_preview_apply() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let target_path = temp.path().join("settings.json");
+        let content = r#"{"model": "opus", "permissions": {"allow": ["Read"]}}"#;
+        fs::write(&target_path, content).unwrap();
+        let source = || -> Settings {
+            serde_json::from_value(serde_json::json!({
+                "model": "sonnet",
+                "permissions": {"allow": ["Read", "Edit"]}
+            }))
+            .unwrap()
+        };
+        let changes = |entries: &[DiffEntry]| -> Vec<String> {
+            entries.iter().map(|entry| entry.to_string()).collect()
+        };
+
+        // Conflicts are listed even though apply without --overwrite would stop
+        let (conflicts, entries) =
+            preview_apply(&target_path, source(), ArrayStrategy::Union).unwrap();
+        assert_eq!(
+            conflicts.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            vec![r#"$.model: "opus" -> "sonnet""#]
+        );
+        assert_eq!(
+            changes(&entries),
+            vec![
+                r#"~ $.model: "opus" -> "sonnet""#,
+                r#"+ $.permissions.allow[1]: "Edit""#
+            ]
+        );
+
+        // The diff follows the array strategy
+        let (_, entries) = preview_apply(&target_path, source(), ArrayStrategy::Append).unwrap();
+        assert_eq!(
+            changes(&entries),
+            vec![
+                r#"~ $.model: "opus" -> "sonnet""#,
+                r#"+ $.permissions.allow[1]: "Read""#,
+                r#"+ $.permissions.allow[2]: "Edit""#
+            ]
+        );
+
+        // The target is left untouched and no backup is taken
+        assert_eq!(fs::read_to_string(&target_path).unwrap(), content);
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 1);
+    }
 }
@@ -0,0 +1,310 @@
+use serde_json::Value;
+use std::io::{self, IsTerminal};
+
+use super::json_path::{format_path, PathSegment};
+
+/// One structural difference between two JSON documents
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffEntry {
+    Added {
+        path: String,
+        value: Value,
+    },
+    Removed {
+        path: String,
+        value: Value,
+    },
+    Changed {
+        path: String,
+        from: Value,
+        to: Value,
+    },
+    /// An element of an ordered array that changed position
+    Moved {
+        from: String,
+        to: String,
+    },
+}
+
+impl DiffEntry {
+    /// `+`, `-`, `~` or `>`, as printed in front of the entry
+    pub fn marker(&self) -> char {
+        match self {
+            DiffEntry::Added { .. } => '+',
+            DiffEntry::Removed { .. } => '-',
+            DiffEntry::Changed { .. } => '~',
+            DiffEntry::Moved { .. } => '>',
+        }
+    }
+
+    /// The entry as displayed, in green, red, yellow or cyan when `color` is set
+    pub fn colored(&self, color: bool) -> String {
+        if !color {
+            return self.to_string();
+        }
+        let code = match self {
+            DiffEntry::Added { .. } => "32",
+            DiffEntry::Removed { .. } => "31",
+            DiffEntry::Changed { .. } => "33",
+            DiffEntry::Moved { .. } => "36",
+        };
+        format!("\x1b[{code}m{self}\x1b[0m")
+    }
+}
+
+/// Whether to color output: stdout is a terminal and `NO_COLOR` is unset
+pub fn use_color() -> bool {
+    io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+impl std::fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiffEntry::Added { path, value } | DiffEntry::Removed { path, value } => {
+                write!(f, "{} {path}: {value}", self.marker())
+            }
+            DiffEntry::Changed { path, from, to } => {
+                write!(f, "{} {path}: {from} -> {to}", self.marker())
+            }
+            DiffEntry::Moved { from, to } => write!(f, "{} {from}: moved to {to}", self.marker()),
+        }
+    }
+}
+
+/// Compare two JSON documents
+///
+/// Objects are compared key by key. Arrays are compared as multisets, since
+/// settings arrays such as permission rules are unordered: entries only in
+/// `a`, or more often in `a` than in `b`, are removals (at their index in `a`)
+/// and the reverse are additions (at their index in `b`).
+///
+/// Under `hooks` order matters, as hooks run in the order they are listed:
+/// elements at the same index are compared field by field, and elements that
+/// changed position are reported as moved.
+pub fn diff_values(a: &Value, b: &Value) -> Vec<DiffEntry> {
+    let mut entries = Vec::new();
+    diff_at(a, b, &mut Vec::new(), &mut entries);
+    entries
+}
+
+fn diff_at(a: &Value, b: &Value, path: &mut Vec<PathSegment>, entries: &mut Vec<DiffEntry>) {
+    match (a, b) {
+        (Value::Object(a_fields), Value::Object(b_fields)) => {
+            let mut keys: Vec<&String> = a_fields.keys().chain(b_fields.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                path.push(PathSegment::Key(key.clone()));
+                match (a_fields.get(key), b_fields.get(key)) {
+                    (Some(a), Some(b)) => diff_at(a, b, path, entries),
+                    (Some(a), None) => entries.push(DiffEntry::Removed {
+                        path: format_path(path),
+                        value: a.clone(),
+                    }),
+                    (None, Some(b)) => entries.push(DiffEntry::Added {
+                        path: format_path(path),
+                        value: b.clone(),
+                    }),
+                    (None, None) => unreachable!("key comes from one of the objects"),
+                }
+                path.pop();
+            }
+        }
+        (Value::Array(a_items), Value::Array(b_items)) => {
+            let ordered = matches!(path.first(), Some(PathSegment::Key(key)) if key == "hooks");
+            diff_arrays(a_items, b_items, ordered, path, entries);
+        }
+        _ if a != b => entries.push(DiffEntry::Changed {
+            path: format_path(path),
+            from: a.clone(),
+            to: b.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn diff_arrays(
+    a_items: &[Value],
+    b_items: &[Value],
+    ordered: bool,
+    path: &mut Vec<PathSegment>,
+    entries: &mut Vec<DiffEntry>,
+) {
+    let at = |path: &mut Vec<PathSegment>, i: usize| {
+        path.push(PathSegment::Index(i));
+        let formatted = format_path(path);
+        path.pop();
+        formatted
+    };
+
+    // Pair equal elements one to one, preferring the same index, so each
+    // duplicate needs a partner of its own
+    let mut partner_of_a: Vec<Option<usize>> = vec![None; a_items.len()];
+    let mut b_taken = vec![false; b_items.len()];
+    for (i, item) in a_items.iter().enumerate() {
+        if b_items.get(i) == Some(item) {
+            partner_of_a[i] = Some(i);
+            b_taken[i] = true;
+        }
+    }
+    for (i, item) in a_items.iter().enumerate() {
+        if partner_of_a[i].is_none() {
+            if let Some(j) = (0..b_items.len()).find(|&j| !b_taken[j] && b_items[j] == *item) {
+                partner_of_a[i] = Some(j);
+                b_taken[j] = true;
+            }
+        }
+    }
+
+    if ordered {
+        // Unpaired elements at the same index were edited in place
+        for i in 0..a_items.len().min(b_items.len()) {
+            if partner_of_a[i].is_none() && !b_taken[i] {
+                path.push(PathSegment::Index(i));
+                diff_at(&a_items[i], &b_items[i], path, entries);
+                path.pop();
+                partner_of_a[i] = Some(i);
+                b_taken[i] = true;
+            }
+        }
+
+        // Pairs outside the longest run that kept its relative order moved
+        let pairs: Vec<(usize, usize)> = partner_of_a
+            .iter()
+            .enumerate()
+            .filter_map(|(i, j)| j.map(|j| (i, j)))
+            .collect();
+        let kept = longest_increasing(&pairs.iter().map(|&(_, j)| j).collect::<Vec<_>>());
+        for (k, &(i, j)) in pairs.iter().enumerate() {
+            if !kept.contains(&k) {
+                entries.push(DiffEntry::Moved {
+                    from: at(path, i),
+                    to: at(path, j),
+                });
+            }
+        }
+    }
+
+    for (i, item) in a_items.iter().enumerate() {
+        if partner_of_a[i].is_none() {
+            entries.push(DiffEntry::Removed {
+                path: at(path, i),
+                value: item.clone(),
+            });
+        }
+    }
+    for (j, item) in b_items.iter().enumerate() {
+        if !b_taken[j] {
+            entries.push(DiffEntry::Added {
+                path: at(path, j),
+                value: item.clone(),
+            });
+        }
+    }
+}
+
+/// Positions in `values` of one longest strictly increasing subsequence
+fn longest_increasing(values: &[usize]) -> Vec<usize> {
+    // Quadratic, but arrays in settings files hold a handful of entries
+    let mut length = vec![1; values.len()];
+    let mut previous = vec![None; values.len()];
+    for i in 0..values.len() {
+        for j in 0..i {
+            if values[j] < values[i] && length[j] + 1 > length[i] {
+                length[i] = length[j] + 1;
+                previous[i] = Some(j);
+            }
+        }
+    }
+
+    let mut positions = Vec::new();
+    let mut current = (0..values.len()).max_by_key(|&i| (length[i], std::cmp::Reverse(i)));
+    while let Some(i) = current {
+        positions.push(i);
+        current = previous[i];
+    }
+    positions
+}
+
+/// Summary line such as `4 change(s): 1 added, 1 removed, 1 changed, 1 moved`
+pub fn diff_summary(entries: &[DiffEntry]) -> String {
+    let count = |marker| entries.iter().filter(|e| e.marker() == marker).count();
+    format!(
+        "{} change(s): {} added, {} removed, {} changed, {} moved",
+        entries.len(),
+        count('+'),
+        count('-'),
+        count('~'),
+        count('>')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_values() {
+        let a = json!({
+            "model": "opus",
+            "env": {"A": "1", "B": "2"},
+            "permissions": {"allow": ["Read", "Bash(ls)"]}
+        });
+        let b = json!({
+            "model": "sonnet",
+            "env": {"A": "1", "C": "3"},
+            "permissions": {"allow": ["Bash(ls)", "Read", "Edit"]}
+        });
+
+        let lines: Vec<String> = diff_values(&a, &b).iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"- $.env.B: "2""#,
+                r#"+ $.env.C: "3""#,
+                r#"~ $.model: "opus" -> "sonnet""#,
+                r#"+ $.permissions.allow[2]: "Edit""#,
+            ]
+        );
+        assert_eq!(
+            diff_summary(&diff_values(&a, &b)),
+            "4 change(s): 2 added, 1 removed, 1 changed, 0 moved"
+        );
+        assert!(diff_values(&a, &a).is_empty());
+
+        // Duplicates count, even in unordered arrays
+        let lines: Vec<String> = diff_values(
+            &json!({"permissions": {"allow": ["Read"]}}),
+            &json!({"permissions": {"allow": ["Read", "Read"]}}),
+        )
+        .iter()
+        .map(|e| e.to_string())
+        .collect();
+        assert_eq!(lines, vec![r#"+ $.permissions.allow[1]: "Read""#]);
+    }
+
+    #[test]
+    fn test_diff_hooks_in_order() {
+        let hook = |command: &str| json!({"type": "command", "command": command});
+        let a = json!({"hooks": {"Stop": [
+            {"matcher": "", "hooks": [hook("a"), hook("b"), hook("c")]},
+            {"matcher": "x", "hooks": [hook("d")]}
+        ]}});
+        let b = json!({"hooks": {"Stop": [
+            {"matcher": "", "hooks": [hook("c"), hook("a"), hook("b"), hook("b")]},
+            {"matcher": "y", "hooks": [hook("d")]}
+        ]}});
+
+        let lines: Vec<String> = diff_values(&a, &b).iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"> $.hooks.Stop[0].hooks[2]: moved to $.hooks.Stop[0].hooks[0]"#,
+                r#"+ $.hooks.Stop[0].hooks[3]: {"command":"b","type":"command"}"#,
+                r#"~ $.hooks.Stop[1].matcher: "x" -> "y""#,
+            ]
+        );
+    }
+}
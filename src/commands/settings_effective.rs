@@ -0,0 +1,183 @@
+use anyhow::Result;
+use claco::{load_settings, settings_files_by_precedence};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::json_path::{format_path, PathSegment};
+
+/// A node of the effective settings, annotated with where it came from
+#[derive(Debug, Clone, PartialEq)]
+enum Resolved {
+    /// A scalar set by `source`, hiding the values of lower-precedence files
+    Value {
+        value: Value,
+        source: &'static str,
+        overridden: Vec<(&'static str, Value)>,
+    },
+    /// Objects merge key by key
+    Object(BTreeMap<String, Resolved>),
+    /// Arrays such as permission rules combine the entries of every file,
+    /// each listing its sources highest precedence first
+    Array(Vec<(Value, Vec<&'static str>)>),
+}
+
+/// Layer `value` from `source` on top of what lower-precedence files set
+fn resolve(current: Option<Resolved>, value: &Value, source: &'static str) -> Resolved {
+    match value {
+        Value::Object(fields) => {
+            let mut merged = match current {
+                Some(Resolved::Object(merged)) => merged,
+                _ => BTreeMap::new(),
+            };
+            for (key, value) in fields {
+                let previous = merged.remove(key);
+                merged.insert(key.clone(), resolve(previous, value, source));
+            }
+            Resolved::Object(merged)
+        }
+        Value::Array(items) => {
+            let mut merged = match current {
+                Some(Resolved::Array(merged)) => merged,
+                _ => Vec::new(),
+            };
+            for item in items {
+                match merged.iter_mut().find(|(existing, _)| existing == item) {
+                    Some((_, sources)) if !sources.contains(&source) => sources.insert(0, source),
+                    Some(_) => {}
+                    None => merged.push((item.clone(), vec![source])),
+                }
+            }
+            Resolved::Array(merged)
+        }
+        _ => {
+            let overridden = match current {
+                Some(Resolved::Value {
+                    value: previous,
+                    source: previous_source,
+                    mut overridden,
+                }) => {
+                    overridden.insert(0, (previous_source, previous));
+                    overridden
+                }
+                _ => Vec::new(),
+            };
+            Resolved::Value {
+                value: value.clone(),
+                source,
+                overridden,
+            }
+        }
+    }
+}
+
+/// Merge settings files given highest precedence first
+fn resolve_files(files: &[(&'static str, Value)]) -> Resolved {
+    files.iter().rev().fold(
+        Resolved::Object(BTreeMap::new()),
+        |merged, (label, value)| resolve(Some(merged), value, label),
+    )
+}
+
+/// One line per effective value: path, value and the file(s) it came from
+fn provenance_lines(node: &Resolved, path: &mut Vec<PathSegment>, lines: &mut Vec<String>) {
+    match node {
+        Resolved::Value {
+            value,
+            source,
+            overridden,
+        } => {
+            lines.push(format!("{} = {value}  [{source}]", format_path(path)));
+            for (source, value) in overridden {
+                lines.push(format!("    overrides [{source}] {value}"));
+            }
+        }
+        Resolved::Object(fields) => {
+            for (key, child) in fields {
+                path.push(PathSegment::Key(key.clone()));
+                provenance_lines(child, path, lines);
+                path.pop();
+            }
+        }
+        Resolved::Array(items) => {
+            for (i, (value, sources)) in items.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                lines.push(format!(
+                    "{} = {value}  [{}]",
+                    format_path(path),
+                    sources.join(", ")
+                ));
+                path.pop();
+            }
+        }
+    }
+}
+
+/// Print the merged settings with the file every value comes from
+///
+/// Higher-precedence files win for single values, objects are merged key by
+/// key, and arrays (permission rules, hooks) combine entries from every file.
+pub fn show_effective_settings() -> Result<()> {
+    let mut files = Vec::new();
+
+    println!("Settings files (highest precedence first):");
+    for (label, settings_path) in settings_files_by_precedence()? {
+        let status = if settings_path.exists() {
+            ""
+        } else {
+            " (not found)"
+        };
+        println!("  [{label}] {}{status}", settings_path.display());
+        files.push((label, serde_json::to_value(load_settings(&settings_path)?)?));
+    }
+    println!();
+
+    let mut lines = Vec::new();
+    provenance_lines(&resolve_files(&files), &mut Vec::new(), &mut lines);
+    if lines.is_empty() {
+        println!("No settings found");
+    }
+    for line in lines {
+        println!("{line}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_effective_provenance() {
+        let files = [
+            ("managed", json!({})),
+            (
+                "project.local",
+                json!({"model": "opus", "env": {"DEBUG": "1"}}),
+            ),
+            (
+                "project",
+                json!({"permissions": {"allow": ["Read", "Bash(ls)"]}}),
+            ),
+            (
+                "user",
+                json!({"model": "sonnet", "env": {"EDITOR": "vim"}, "permissions": {"allow": ["Read"]}}),
+            ),
+        ];
+
+        let mut lines = Vec::new();
+        provenance_lines(&resolve_files(&files), &mut Vec::new(), &mut lines);
+        assert_eq!(
+            lines,
+            vec![
+                r#"$.env.DEBUG = "1"  [project.local]"#,
+                r#"$.env.EDITOR = "vim"  [user]"#,
+                r#"$.model = "opus"  [project.local]"#,
+                r#"    overrides [user] "sonnet""#,
+                r#"$.permissions.allow[0] = "Read"  [project, user]"#,
+                r#"$.permissions.allow[1] = "Bash(ls)"  [project]"#,
+            ]
+        );
+    }
+}
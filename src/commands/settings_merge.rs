@@ -0,0 +1,219 @@
+use claco::{ArrayStrategy, HookMatcher, Hooks, Settings};
+use serde_json::Value;
+
+use super::json_path::{format_path, PathSegment};
+
+/// A path where the source and target settings hold different values
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub path: String,
+    pub current: Value,
+    pub incoming: Value,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.path, self.current, self.incoming)
+    }
+}
+
+/// Merge source settings into target settings
+///
+/// Objects are merged recursively and arrays are combined by `strategy`.
+/// Hooks are merged per event and matcher, skipping hooks the target already
+/// has. Where both sides hold different values that cannot be combined, a
+/// conflict is reported; the source value wins only when `overwrite` is set.
+pub fn merge_settings(
+    target: &mut Settings,
+    source: Settings,
+    overwrite: bool,
+    strategy: ArrayStrategy,
+) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+
+    if let Some(source_hooks) = source.hooks {
+        merge_hooks(
+            target.hooks.get_or_insert_with(Default::default),
+            source_hooks,
+            strategy,
+        );
+    }
+
+    let mut keys: Vec<String> = source.other.keys().cloned().collect();
+    keys.sort();
+    let mut source_other = source.other;
+    for key in keys {
+        let value = source_other.remove(&key).unwrap_or_default();
+        match target.other.get_mut(&key) {
+            Some(existing) => {
+                let mut path = vec![PathSegment::Key(key)];
+                merge_values(
+                    existing,
+                    value,
+                    overwrite,
+                    strategy,
+                    &mut path,
+                    &mut conflicts,
+                );
+            }
+            None => {
+                target.other.insert(key, value);
+            }
+        }
+    }
+
+    conflicts
+}
+
+fn merge_values(
+    target: &mut Value,
+    source: Value,
+    overwrite: bool,
+    strategy: ArrayStrategy,
+    path: &mut Vec<PathSegment>,
+    conflicts: &mut Vec<Conflict>,
+) {
+    match (target, source) {
+        (Value::Object(target_fields), Value::Object(source_fields)) => {
+            for (key, value) in source_fields {
+                match target_fields.get_mut(&key) {
+                    Some(existing) => {
+                        path.push(PathSegment::Key(key));
+                        merge_values(existing, value, overwrite, strategy, path, conflicts);
+                        path.pop();
+                    }
+                    None => {
+                        target_fields.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(target_items), Value::Array(source_items)) => match strategy {
+            ArrayStrategy::Union => {
+                for item in source_items {
+                    if !target_items.contains(&item) {
+                        target_items.push(item);
+                    }
+                }
+            }
+            ArrayStrategy::Append => target_items.extend(source_items),
+            ArrayStrategy::Replace => *target_items = source_items,
+        },
+        (target, source) if *target == source => {}
+        (target, source) => {
+            conflicts.push(Conflict {
+                path: format_path(path),
+                current: target.clone(),
+                incoming: source.clone(),
+            });
+            if overwrite {
+                *target = source;
+            }
+        }
+    }
+}
+
+/// Merge hooks per event, adding source hooks to the matcher they belong to
+///
+/// Identical hooks are never added twice. With the replace strategy an
+/// event's matchers from the source replace the target's.
+fn merge_hooks(target: &mut Hooks, source: Hooks, strategy: ArrayStrategy) {
+    for (event, matchers) in source {
+        if strategy == ArrayStrategy::Replace {
+            target.insert(event, matchers);
+            continue;
+        }
+
+        let target_matchers = target.entry(event).or_default();
+        for matcher in matchers {
+            let existing = match target_matchers
+                .iter_mut()
+                .find(|existing| existing.matcher == matcher.matcher)
+            {
+                Some(existing) => existing,
+                None => {
+                    target_matchers.push(HookMatcher {
+                        hooks: Vec::new(),
+                        ..matcher.clone()
+                    });
+                    target_matchers.last_mut().unwrap()
+                }
+            };
+            for hook in matcher.hooks {
+                if !existing.hooks.contains(&hook) {
+                    existing.hooks.push(hook);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claco::HookEvent;
+    use serde_json::json;
+
+    fn settings(value: Value) -> Settings {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_merge_settings() {
+        let base = json!({
+            "model": "opus",
+            "permissions": {"allow": ["Read", "Bash(ls)"], "defaultMode": "default"},
+            "hooks": {"Stop": [{"matcher": "", "hooks": [{"type": "command", "command": "a"}]}]}
+        });
+        let shared = json!({
+            "model": "sonnet",
+            "env": {"X": "1"},
+            "permissions": {"allow": ["Read", "Edit"], "defaultMode": "default"},
+            "hooks": {"Stop": [{"matcher": "", "hooks": [
+                {"type": "command", "command": "a"},
+                {"type": "command", "command": "b"}
+            ]}]}
+        });
+
+        let mut target = settings(base.clone());
+        let conflicts = merge_settings(
+            &mut target,
+            settings(shared.clone()),
+            false,
+            ArrayStrategy::Union,
+        );
+        assert_eq!(
+            conflicts.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            vec![r#"$.model: "opus" -> "sonnet""#]
+        );
+        assert_eq!(target.other["model"], "opus");
+        assert_eq!(target.other["env"], json!({"X": "1"}));
+        assert_eq!(
+            target.other["permissions"]["allow"],
+            json!(["Read", "Bash(ls)", "Edit"])
+        );
+        let stop = &target.hooks.as_ref().unwrap()[&HookEvent::Stop];
+        assert_eq!(stop.len(), 1);
+        assert_eq!(stop[0].hooks.len(), 2);
+
+        let mut target = settings(base.clone());
+        merge_settings(
+            &mut target,
+            settings(shared.clone()),
+            true,
+            ArrayStrategy::Append,
+        );
+        assert_eq!(target.other["model"], "sonnet");
+        assert_eq!(
+            target.other["permissions"]["allow"],
+            json!(["Read", "Bash(ls)", "Read", "Edit"])
+        );
+
+        let mut target = settings(base);
+        merge_settings(&mut target, settings(shared), false, ArrayStrategy::Replace);
+        assert_eq!(
+            target.other["permissions"]["allow"],
+            json!(["Read", "Edit"])
+        );
+    }
+}
@@ -1,5 +1,9 @@
 use anyhow::Result;
+use claco::transcript::{
+    find_project_dir_for_cwd, project_dirs, read_transcript, session_files, Transcript,
+};
 use claco::{claude_home, CommandsSubcommand, Scope};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{self, Write};
 use std::process::Command;
@@ -7,6 +11,49 @@ use std::process::Command;
 // Constants
 const MAX_GITHUB_FILE_SIZE: usize = 10 * 1024 * 1024; // 10MB
 
+/// Slash commands built into Claude Code, never reported as missing
+const BUILTIN_COMMANDS: &[&str] = &[
+    "/add-dir",
+    "/agents",
+    "/bashes",
+    "/bug",
+    "/clear",
+    "/compact",
+    "/config",
+    "/context",
+    "/cost",
+    "/doctor",
+    "/exit",
+    "/export",
+    "/help",
+    "/hooks",
+    "/ide",
+    "/init",
+    "/install-github-app",
+    "/login",
+    "/logout",
+    "/mcp",
+    "/memory",
+    "/model",
+    "/output-style",
+    "/permissions",
+    "/plugin",
+    "/pr-comments",
+    "/privacy-settings",
+    "/release-notes",
+    "/resume",
+    "/review",
+    "/rewind",
+    "/security-review",
+    "/status",
+    "/statusline",
+    "/terminal-setup",
+    "/todos",
+    "/upgrade",
+    "/usage",
+    "/vim",
+];
+
 /// Handle slash command-related subcommands
 ///
 /// This function processes all slash command management operations including:
@@ -15,6 +62,7 @@ const MAX_GITHUB_FILE_SIZE: usize = 10 * 1024 * 1024; // 10MB
 /// - Deleting commands interactively
 /// - Cleaning up all commands in a scope
 /// - Generating new commands using Claude
+/// - Reporting slash command usage from session transcripts
 pub async fn handle_commands(cmd: CommandsSubcommand) -> Result<()> {
     match cmd {
         CommandsSubcommand::List { scope } => handle_commands_list(scope)?,
@@ -22,6 +70,7 @@ pub async fn handle_commands(cmd: CommandsSubcommand) -> Result<()> {
         CommandsSubcommand::Clean { scope } => handle_commands_clean(scope)?,
         CommandsSubcommand::Generate { filename } => handle_commands_generate(filename)?,
         CommandsSubcommand::Delete { interactive } => handle_commands_delete(interactive)?,
+        CommandsSubcommand::Stats => handle_commands_stats()?,
     }
     Ok(())
 }
@@ -790,6 +839,144 @@ fn collect_commands_recursive(
     Ok(())
 }
 
+/// Slash command usage across sessions, checked against the installed commands
+#[derive(Debug, Default)]
+struct CommandStats {
+    /// Installed commands per scope with their invocation counts, most used first
+    scopes: Vec<(&'static str, Vec<(String, usize)>)>,
+    /// Installed commands that were never invoked, with their scope
+    never_used: Vec<(String, &'static str)>,
+    /// Commands invoked in the current project that are neither installed nor built in
+    missing: Vec<(String, usize)>,
+}
+
+/// Count slash command invocations and match them to installed commands
+///
+/// `installed` lists command names per scope label; `sessions` pairs each
+/// transcript with whether it belongs to the current project. User commands
+/// are counted across all sessions, project commands only across the current
+/// project's.
+fn command_stats(
+    installed: &[(&'static str, Vec<String>)],
+    sessions: &[(bool, Transcript)],
+) -> CommandStats {
+    let mut all_counts: BTreeMap<String, usize> = BTreeMap::new();
+    let mut project_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for (is_current, transcript) in sessions {
+        for command in transcript.slash_commands() {
+            if *is_current {
+                *project_counts.entry(command.clone()).or_insert(0) += 1;
+            }
+            *all_counts.entry(command).or_insert(0) += 1;
+        }
+    }
+
+    let mut stats = CommandStats::default();
+    for (scope_label, names) in installed {
+        let counts = if *scope_label == "project" {
+            &project_counts
+        } else {
+            &all_counts
+        };
+
+        // Most used first, then alphabetical
+        let mut usage: Vec<(String, usize)> = names
+            .iter()
+            .map(|name| (name.clone(), counts.get(name).copied().unwrap_or(0)))
+            .collect();
+        usage.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        stats.never_used.extend(
+            usage
+                .iter()
+                .filter(|(_, count)| *count == 0)
+                .map(|(name, _)| (name.clone(), *scope_label)),
+        );
+        stats.scopes.push((scope_label, usage));
+    }
+
+    // Only the current project's invocations can be checked against installed commands
+    stats.missing = project_counts
+        .into_iter()
+        .filter(|(name, _)| {
+            !installed.iter().any(|(_, names)| names.contains(name))
+                && !BUILTIN_COMMANDS.contains(&name.as_str())
+                && !name.starts_with("/mcp__")
+        })
+        .collect();
+
+    stats
+}
+
+/// Count slash command invocations across sessions
+///
+/// User commands are counted across all projects, project commands only across
+/// sessions of the current project. Reports usage per scope, commands that were
+/// never used, and invoked commands that are neither installed nor built in.
+fn handle_commands_stats() -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let current_project = find_project_dir_for_cwd(&cwd.to_string_lossy())?;
+
+    let mut sessions = Vec::new();
+    for project_path in project_dirs()? {
+        let is_current = current_project.as_ref() == Some(&project_path);
+        for session_path in session_files(&project_path)? {
+            if let Ok(transcript) = read_transcript(&session_path) {
+                sessions.push((is_current, transcript));
+            }
+        }
+    }
+
+    let mut installed = Vec::new();
+    let mut dirs = Vec::new();
+    for (scope, scope_label) in [(Scope::User, "user"), (Scope::Project, "project")] {
+        let commands_dir = get_commands_dir(&scope)?;
+        if !commands_dir.exists() {
+            continue;
+        }
+
+        let mut commands_list = Vec::new();
+        collect_commands_recursive(&commands_dir, "", &scope, &mut commands_list)?;
+        if commands_list.is_empty() {
+            continue;
+        }
+        installed.push((
+            scope_label,
+            commands_list.into_iter().map(|(name, _, _)| name).collect(),
+        ));
+        dirs.push(commands_dir);
+    }
+
+    let stats = command_stats(&installed, &sessions);
+    println!("Slash command usage ({} session(s))", sessions.len());
+
+    for ((scope_label, usage), commands_dir) in stats.scopes.iter().zip(&dirs) {
+        println!();
+        println!("Commands ({}): {}", scope_label, commands_dir.display());
+        for (name, count) in usage {
+            println!("  {name} {count}");
+        }
+    }
+
+    if !stats.never_used.is_empty() {
+        println!();
+        println!("Never used:");
+        for (name, scope_label) in &stats.never_used {
+            println!("  {name} [{scope_label}]");
+        }
+    }
+
+    if !stats.missing.is_empty() {
+        println!();
+        println!("Invoked but not installed:");
+        for (name, count) in &stats.missing {
+            println!("  {name} ({count} invocation(s))");
+        }
+    }
+
+    Ok(())
+}
+
 fn handle_commands_generate(filename: Option<String>) -> Result<()> {
     // Generate template markdown
     let template_content = r#"---
@@ -849,3 +1036,70 @@ $ARGUMENTS
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript_from(content: &str) -> Transcript {
+        let (entries, failures) = claco::transcript::parse_transcript(content);
+        Transcript {
+            path: std::path::PathBuf::from("session.jsonl"),
+            entries,
+            failures,
+        }
+    }
+
+    fn invoking(commands: &[&str]) -> Transcript {
+        let lines: Vec<String> = commands
+            .iter()
+            .map(|command| {
+                format!(
+                    r#"{{"type":"user","message":{{"role":"user","content":"<command-name>{command}</command-name>"}}}}"#
+                )
+            })
+            .collect();
+        transcript_from(&lines.join("\n"))
+    }
+
+    #[test]
+    fn test_command_stats() {
+        let sessions = [
+            (true, invoking(&["/review", "/deploy", "/review", "/clear"])),
+            (true, invoking(&["/old-command", "/mcp__github__pr"])),
+            // Other projects count towards user commands only
+            (false, invoking(&["/review", "/deploy", "/elsewhere"])),
+        ];
+        let installed = [
+            ("user", vec!["/review".to_string(), "/standup".to_string()]),
+            (
+                "project",
+                vec!["/deploy".to_string(), "/release".to_string()],
+            ),
+        ];
+
+        let stats = command_stats(&installed, &sessions);
+        assert_eq!(
+            stats.scopes,
+            vec![
+                (
+                    "user",
+                    vec![("/review".to_string(), 3), ("/standup".to_string(), 0)]
+                ),
+                (
+                    "project",
+                    vec![("/deploy".to_string(), 1), ("/release".to_string(), 0)]
+                ),
+            ]
+        );
+        assert_eq!(
+            stats.never_used,
+            vec![
+                ("/standup".to_string(), "user"),
+                ("/release".to_string(), "project")
+            ]
+        );
+        // Built-in and MCP commands, and other projects' commands, are not missing
+        assert_eq!(stats.missing, vec![("/old-command".to_string(), 1)]);
+    }
+}
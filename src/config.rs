@@ -1,6 +1,7 @@
 use anyhow::Result;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -9,6 +10,44 @@ pub struct Config {
     pub verbose: bool,
     pub log_level: String,
     pub data_dir: PathBuf,
+    /// Where `claco notify` forwards hook notifications
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify: Vec<NotifySink>,
+    /// Snapshots kept per settings file before it is overwritten; 0 disables backups
+    #[serde(default = "default_settings_backups")]
+    pub settings_backups: usize,
+}
+
+fn default_settings_backups() -> usize {
+    20
+}
+
+/// A destination for `claco notify`
+///
+/// Templates may use `{{title}}`, `{{message}}`, `{{event}}`, `{{project}}`,
+/// `{{cwd}}`, `{{session_id}}` and `{{timestamp}}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotifySink {
+    /// POST a templated JSON body, e.g. to a Slack or Discord webhook
+    Webhook {
+        url: String,
+        /// Body template; values are JSON-escaped (defaults to `{"text": ...}`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body: Option<String>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+    },
+    /// Run a shell command such as `notify-send`; values are shell-quoted
+    Command { command: String },
+    /// Ring the terminal bell
+    Bell,
+    /// Append a templated line to a file
+    File {
+        path: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+    },
 }
 
 impl Default for Config {
@@ -17,6 +56,8 @@ impl Default for Config {
             verbose: false,
             log_level: "info".to_string(),
             data_dir: Self::default_data_dir(),
+            notify: Vec::new(),
+            settings_backups: default_settings_backups(),
         }
     }
 }
@@ -46,7 +87,7 @@ impl Config {
         Ok(())
     }
 
-    fn config_path() -> Result<PathBuf> {
+    pub fn config_path() -> Result<PathBuf> {
         let proj_dirs = ProjectDirs::from("com", "thekaiway", "claco")
             .ok_or_else(|| anyhow::anyhow!("Unable to find config directory"))?;
 
@@ -0,0 +1,347 @@
+use crate::claude::HookEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Fields Claude Code sends with every hook event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookCommon {
+    pub session_id: String,
+    pub transcript_path: String,
+    pub cwd: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_mode: Option<String>,
+}
+
+/// Payload for PreToolUse hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreToolUsePayload {
+    #[serde(flatten)]
+    pub common: HookCommon,
+    pub tool_name: String,
+    #[serde(default)]
+    pub tool_input: Value,
+}
+
+/// Payload for PostToolUse hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostToolUsePayload {
+    #[serde(flatten)]
+    pub common: HookCommon,
+    pub tool_name: String,
+    #[serde(default)]
+    pub tool_input: Value,
+    #[serde(default)]
+    pub tool_response: Value,
+}
+
+/// Payload for Notification hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationPayload {
+    #[serde(flatten)]
+    pub common: HookCommon,
+    pub message: String,
+}
+
+/// Payload for UserPromptSubmit hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserPromptSubmitPayload {
+    #[serde(flatten)]
+    pub common: HookCommon,
+    pub prompt: String,
+}
+
+/// Payload for Stop and SubagentStop hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StopPayload {
+    #[serde(flatten)]
+    pub common: HookCommon,
+    /// True when Claude is already continuing because of a stop hook
+    #[serde(default)]
+    pub stop_hook_active: bool,
+}
+
+/// Payload for PreCompact hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreCompactPayload {
+    #[serde(flatten)]
+    pub common: HookCommon,
+    /// `manual` or `auto`
+    pub trigger: String,
+    #[serde(default)]
+    pub custom_instructions: String,
+}
+
+/// Payload for SessionStart hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionStartPayload {
+    #[serde(flatten)]
+    pub common: HookCommon,
+    /// `startup`, `resume`, `clear` or `compact`
+    pub source: String,
+}
+
+/// Payload for SessionEnd hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEndPayload {
+    #[serde(flatten)]
+    pub common: HookCommon,
+    pub reason: String,
+}
+
+/// JSON a hook receives on stdin, tagged by `hook_event_name`
+///
+/// Unknown fields are ignored, so handlers keep working when Claude Code
+/// adds new ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "hook_event_name")]
+pub enum HookPayload {
+    PreToolUse(PreToolUsePayload),
+    PostToolUse(PostToolUsePayload),
+    Notification(NotificationPayload),
+    UserPromptSubmit(UserPromptSubmitPayload),
+    Stop(StopPayload),
+    SubagentStop(StopPayload),
+    PreCompact(PreCompactPayload),
+    SessionStart(SessionStartPayload),
+    SessionEnd(SessionEndPayload),
+}
+
+impl HookPayload {
+    /// Event this payload was sent for
+    pub fn event(&self) -> HookEvent {
+        match self {
+            HookPayload::PreToolUse(_) => HookEvent::PreToolUse,
+            HookPayload::PostToolUse(_) => HookEvent::PostToolUse,
+            HookPayload::Notification(_) => HookEvent::Notification,
+            HookPayload::UserPromptSubmit(_) => HookEvent::UserPromptSubmit,
+            HookPayload::Stop(_) => HookEvent::Stop,
+            HookPayload::SubagentStop(_) => HookEvent::SubagentStop,
+            HookPayload::PreCompact(_) => HookEvent::PreCompact,
+            HookPayload::SessionStart(_) => HookEvent::SessionStart,
+            HookPayload::SessionEnd(_) => HookEvent::SessionEnd,
+        }
+    }
+
+    pub fn common(&self) -> &HookCommon {
+        match self {
+            HookPayload::PreToolUse(p) => &p.common,
+            HookPayload::PostToolUse(p) => &p.common,
+            HookPayload::Notification(p) => &p.common,
+            HookPayload::UserPromptSubmit(p) => &p.common,
+            HookPayload::Stop(p) | HookPayload::SubagentStop(p) => &p.common,
+            HookPayload::PreCompact(p) => &p.common,
+            HookPayload::SessionStart(p) => &p.common,
+            HookPayload::SessionEnd(p) => &p.common,
+        }
+    }
+
+    /// Tool name for tool events
+    pub fn tool_name(&self) -> Option<&str> {
+        match self {
+            HookPayload::PreToolUse(p) => Some(&p.tool_name),
+            HookPayload::PostToolUse(p) => Some(&p.tool_name),
+            _ => None,
+        }
+    }
+
+    /// Tool input for tool events
+    pub fn tool_input(&self) -> Option<&Value> {
+        match self {
+            HookPayload::PreToolUse(p) => Some(&p.tool_input),
+            HookPayload::PostToolUse(p) => Some(&p.tool_input),
+            _ => None,
+        }
+    }
+
+    /// Value hook matchers are compared against, for events that use matchers
+    pub fn matcher_value(&self) -> Option<&str> {
+        match self {
+            HookPayload::PreToolUse(p) => Some(&p.tool_name),
+            HookPayload::PostToolUse(p) => Some(&p.tool_name),
+            HookPayload::PreCompact(p) => Some(&p.trigger),
+            HookPayload::SessionStart(p) => Some(&p.source),
+            _ => None,
+        }
+    }
+}
+
+/// Legacy top-level decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    /// Bypass the permission prompt (PreToolUse only)
+    Approve,
+    /// Block the tool call, prompt or stop, feeding `reason` back to Claude
+    Block,
+}
+
+/// Permission decision returned by PreToolUse hooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionDecision {
+    Allow,
+    Deny,
+    Ask,
+}
+
+/// Event-specific part of a hook response, tagged by `hookEventName`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "hookEventName", rename_all_fields = "camelCase")]
+pub enum HookSpecificOutput {
+    PreToolUse {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        permission_decision: Option<PermissionDecision>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        permission_decision_reason: Option<String>,
+    },
+    PostToolUse {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        additional_context: Option<String>,
+    },
+    UserPromptSubmit {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        additional_context: Option<String>,
+    },
+    SessionStart {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        additional_context: Option<String>,
+    },
+}
+
+/// Structured JSON a hook may print on stdout
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookResponse {
+    /// `false` stops Claude entirely, showing `stop_reason` to the user
+    #[serde(rename = "continue", default, skip_serializing_if = "Option::is_none")]
+    pub should_continue: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+    /// Hide stdout from the transcript
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppress_output: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision: Option<Decision>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook_specific_output: Option<HookSpecificOutput>,
+}
+
+impl HookResponse {
+    /// PreToolUse response with a permission decision and reason
+    pub fn permission(decision: PermissionDecision, reason: impl Into<String>) -> Self {
+        Self {
+            hook_specific_output: Some(HookSpecificOutput::PreToolUse {
+                permission_decision: Some(decision),
+                permission_decision_reason: Some(reason.into()),
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Response that blocks the action and feeds `reason` back to Claude
+    pub fn block(reason: impl Into<String>) -> Self {
+        Self {
+            decision: Some(Decision::Block),
+            reason: Some(reason.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Short label for the decision this response makes, if any
+    ///
+    /// One of `allow`, `deny`, `ask`, `approve`, `block` or `stop`.
+    pub fn decision_label(&self) -> Option<&'static str> {
+        if let Some(HookSpecificOutput::PreToolUse {
+            permission_decision: Some(decision),
+            ..
+        }) = self.hook_specific_output
+        {
+            return Some(match decision {
+                PermissionDecision::Allow => "allow",
+                PermissionDecision::Deny => "deny",
+                PermissionDecision::Ask => "ask",
+            });
+        }
+        match self.decision {
+            Some(Decision::Approve) => Some("approve"),
+            Some(Decision::Block) => Some("block"),
+            None if self.should_continue == Some(false) => Some("stop"),
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hook_payload_round_trip() {
+        let value = json!({
+            "session_id": "abc",
+            "transcript_path": "/tmp/abc.jsonl",
+            "cwd": "/tmp",
+            "permission_mode": "default",
+            "hook_event_name": "PreToolUse",
+            "tool_name": "Bash",
+            "tool_input": {"command": "ls"},
+            "some_future_field": true
+        });
+
+        let payload: HookPayload = serde_json::from_value(value).unwrap();
+        assert_eq!(payload.event(), HookEvent::PreToolUse);
+        assert_eq!(payload.matcher_value(), Some("Bash"));
+        assert_eq!(payload.tool_input().unwrap()["command"], "ls");
+        assert_eq!(payload.common().permission_mode.as_deref(), Some("default"));
+
+        let value = serde_json::to_value(&payload).unwrap();
+        assert_eq!(value["hook_event_name"], "PreToolUse");
+        assert_eq!(value["session_id"], "abc");
+
+        let stop: HookPayload = serde_json::from_value(json!({
+            "session_id": "abc",
+            "transcript_path": "/tmp/abc.jsonl",
+            "cwd": "/tmp",
+            "hook_event_name": "SubagentStop"
+        }))
+        .unwrap();
+        assert_eq!(stop.event(), HookEvent::SubagentStop);
+        assert_eq!(stop.matcher_value(), None);
+    }
+
+    #[test]
+    fn test_hook_response() {
+        let response = HookResponse::permission(PermissionDecision::Deny, "no");
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "hookSpecificOutput": {
+                    "hookEventName": "PreToolUse",
+                    "permissionDecision": "deny",
+                    "permissionDecisionReason": "no"
+                }
+            })
+        );
+        assert_eq!(response.decision_label(), Some("deny"));
+
+        let response: HookResponse = serde_json::from_value(json!({
+            "continue": false,
+            "stopReason": "done",
+            "hookSpecificOutput": {
+                "hookEventName": "SessionStart",
+                "additionalContext": "branch: main"
+            }
+        }))
+        .unwrap();
+        assert_eq!(response.should_continue, Some(false));
+        assert_eq!(response.decision_label(), Some("stop"));
+
+        assert_eq!(HookResponse::block("x").decision_label(), Some("block"));
+        assert_eq!(HookResponse::default().decision_label(), None);
+    }
+}
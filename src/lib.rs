@@ -1,7 +1,13 @@
 pub mod claude;
 pub mod cli;
 pub mod config;
+pub mod hook_payload;
+pub mod settings_backup;
+pub mod transcript;
 
 pub use claude::*;
-pub use cli::{AgentsSubcommand, Cli, Commands, CommandsSubcommand, HooksAction, Scope};
-pub use config::Config;
+pub use cli::{
+    AgentsSubcommand, ArrayStrategy, Cli, Commands, CommandsSubcommand, HookId, HooksAction, Scope,
+    ScriptLang, SessionAction,
+};
+pub use config::{Config, NotifySink};
@@ -24,10 +24,20 @@ async fn main() -> Result<()> {
     match cli.command {
         Commands::Agents(cmd) => commands::handle_agents(cmd).await?,
         Commands::Commands(cmd) => commands::handle_commands(cmd).await?,
-        Commands::Hooks { action } => commands::handle_hooks(action)?,
+        Commands::Hooks { action } => commands::handle_hooks(action).await?,
         Commands::History { session } => commands::handle_history(session)?,
-        Commands::Session { session_id } => commands::handle_session(session_id)?,
+        Commands::Session { action, session_id } => commands::handle_session(action, session_id)?,
         Commands::Projects => commands::handle_projects()?,
+        Commands::Guard {
+            policy,
+            show_policy,
+        } => commands::handle_guard(policy, show_policy)?,
+        Commands::HookExec {
+            matcher,
+            timeout,
+            command,
+        } => commands::handle_hook_exec(command, matcher, timeout)?,
+        Commands::Notify { message, test } => commands::handle_notify(message, test).await?,
         Commands::Settings(cmd) => commands::handle_settings(cmd).await?,
     }
 
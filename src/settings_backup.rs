@@ -0,0 +1,222 @@
+//! Snapshots of settings files, taken before claco overwrites them
+//!
+//! Snapshots live in `<data_dir>/backups/<settings path key>/` and are named
+//! after the UTC time they were taken, e.g. `20261018T153012.123Z.json`, so
+//! they sort oldest to newest by name.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use crate::claude::sanitize_project_path;
+use crate::config::Config;
+
+const ID_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// A saved copy of a settings file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    /// File stem, as accepted by `settings restore`
+    pub id: String,
+    pub path: PathBuf,
+    pub created: DateTime<Utc>,
+    pub size: u64,
+}
+
+/// Where snapshots are kept and how many per settings file
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupTarget {
+    pub data_dir: PathBuf,
+    pub keep: usize,
+}
+
+impl BackupTarget {
+    /// Target from claco's config, or `None` when `settings_backups` is 0
+    pub fn from_config(config: &Config) -> Option<Self> {
+        (config.settings_backups > 0).then(|| BackupTarget {
+            data_dir: config.data_dir.clone(),
+            keep: config.settings_backups,
+        })
+    }
+
+    /// Target from the config file, warning instead of failing when it cannot be loaded
+    pub fn configured() -> Option<Self> {
+        match Config::load() {
+            Ok(config) => Self::from_config(&config),
+            Err(e) => {
+                eprintln!("warning: settings backups are off, failed to load claco config: {e:#}");
+                None
+            }
+        }
+    }
+
+    /// Directory holding the snapshots of one settings file
+    pub fn dir_for(&self, settings_path: &Path) -> Result<PathBuf> {
+        backup_dir(&self.data_dir, settings_path)
+    }
+
+    /// Snapshot the current content of `settings_path`
+    pub fn snapshot(&self, settings_path: &Path) -> Result<Option<Snapshot>> {
+        snapshot_settings(&self.dir_for(settings_path)?, settings_path, self.keep)
+    }
+}
+
+/// Directory holding the snapshots of one settings file
+///
+/// The name is the readable sanitized path plus a hash of the exact path, as
+/// sanitizing alone maps `/a/b-c` and `/a-b/c` to the same name.
+pub fn backup_dir(data_dir: &Path, settings_path: &Path) -> Result<PathBuf> {
+    let absolute = std::path::absolute(settings_path)
+        .with_context(|| format!("Failed to resolve {}", settings_path.display()))?;
+    let absolute = absolute.to_string_lossy();
+    Ok(data_dir.join("backups").join(format!(
+        "{}-{:016x}",
+        sanitize_project_path(&absolute),
+        fnv1a(absolute.as_bytes())
+    )))
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Snapshots of a settings file, newest first
+pub fn list_snapshots(dir: &Path) -> Result<Vec<Snapshot>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        // Skip files that were not written by `snapshot_settings`
+        let Ok(created) = NaiveDateTime::parse_from_str(id, ID_FORMAT) else {
+            continue;
+        };
+        snapshots.push(Snapshot {
+            id: id.to_string(),
+            size: fs::metadata(&path)?.len(),
+            created: created.and_utc(),
+            path,
+        });
+    }
+
+    snapshots.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(snapshots)
+}
+
+/// Copy the current content of `settings_path` into `dir`, keeping the newest `keep` snapshots
+///
+/// Nothing is written when the file does not exist yet or is unchanged since
+/// the latest snapshot.
+pub fn snapshot_settings(
+    dir: &Path,
+    settings_path: &Path,
+    keep: usize,
+) -> Result<Option<Snapshot>> {
+    let content = match fs::read(settings_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", settings_path.display()))
+        }
+    };
+
+    let existing = list_snapshots(dir)?;
+    if let Some(latest) = existing.first() {
+        if fs::read(&latest.path).is_ok_and(|latest| latest == content) {
+            return Ok(None);
+        }
+    }
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create backup directory: {}", dir.display()))?;
+
+    // Two saves within the same millisecond take the next free one
+    let mut created = Utc::now();
+    let (mut file, id, path) = loop {
+        let id = created.format(ID_FORMAT).to_string();
+        let path = dir.join(format!("{id}.json"));
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => break (file, id, path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                created += Duration::milliseconds(1);
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to create {}", path.display()))
+            }
+        }
+    };
+    file.write_all(&content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    for old in list_snapshots(dir)?.iter().skip(keep) {
+        fs::remove_file(&old.path)
+            .with_context(|| format!("Failed to remove old backup {}", old.path.display()))?;
+    }
+
+    Ok(Some(Snapshot {
+        id,
+        path,
+        created,
+        size: content.len() as u64,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_settings() {
+        let temp = TempDir::new().unwrap();
+        let settings_path = temp.path().join("settings.json");
+        let dir = backup_dir(&temp.path().join("data"), &settings_path).unwrap();
+        assert!(dir.starts_with(temp.path().join("data").join("backups")));
+        // Paths that sanitize to the same name still get their own directory
+        assert_ne!(
+            backup_dir(temp.path(), Path::new("/a/b-c/settings.json")).unwrap(),
+            backup_dir(temp.path(), Path::new("/a-b/c/settings.json")).unwrap()
+        );
+
+        // Nothing to back up before the file exists
+        assert_eq!(snapshot_settings(&dir, &settings_path, 2).unwrap(), None);
+
+        for model in ["opus", "sonnet", "haiku"] {
+            fs::write(&settings_path, format!(r#"{{"model": "{model}"}}"#)).unwrap();
+            assert!(snapshot_settings(&dir, &settings_path, 2)
+                .unwrap()
+                .is_some());
+            // Unchanged content is not snapshotted twice
+            assert_eq!(snapshot_settings(&dir, &settings_path, 2).unwrap(), None);
+        }
+
+        let snapshots = list_snapshots(&dir).unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert!(snapshots[0].id > snapshots[1].id);
+        assert_eq!(
+            fs::read_to_string(&snapshots[0].path).unwrap(),
+            r#"{"model": "haiku"}"#
+        );
+        assert_eq!(
+            fs::read_to_string(&snapshots[1].path).unwrap(),
+            r#"{"model": "sonnet"}"#
+        );
+    }
+}
@@ -0,0 +1,1007 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::claude::{claude_home, ContentBlock, EntryKind, SessionEntry};
+use std::collections::HashMap;
+
+/// Maximum length of a session title derived from the first prompt
+pub const MAX_TITLE_LEN: usize = 60;
+
+/// A successfully parsed line of a session transcript
+#[derive(Debug, Clone)]
+pub struct TranscriptLine {
+    /// 1-based line number in the JSONL file
+    pub line: usize,
+    pub entry: SessionEntry,
+}
+
+/// A line of a session transcript that could not be parsed
+#[derive(Debug, Clone)]
+pub struct ParseFailure {
+    /// 1-based line number in the JSONL file
+    pub line: usize,
+    pub error: String,
+}
+
+/// A session transcript read tolerantly: bad lines are collected instead of aborting
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    pub path: PathBuf,
+    pub entries: Vec<TranscriptLine>,
+    pub failures: Vec<ParseFailure>,
+}
+
+impl Transcript {
+    /// Session ID derived from the transcript file name
+    pub fn session_id(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Title generated by Claude Code from a `summary` entry
+    ///
+    /// Summaries may be written for other conversations when a session is resumed,
+    /// so a summary whose `leafUuid` points into this transcript wins; otherwise
+    /// the first summary in the file is used.
+    pub fn summary_title(&self) -> Option<String> {
+        let summaries: Vec<&SessionEntry> = self
+            .entries
+            .iter()
+            .map(|line| &line.entry)
+            .filter(|entry| entry.kind() == EntryKind::Summary && entry.summary.is_some())
+            .collect();
+
+        let own_summary = summaries.iter().rev().find(|summary| {
+            self.entries
+                .iter()
+                .any(|line| line.entry.uuid.is_some() && line.entry.uuid == summary.leaf_uuid)
+        });
+
+        own_summary
+            .or(summaries.first())
+            .and_then(|entry| entry.summary.clone())
+    }
+
+    /// First prompt typed by the user, with slash commands shown as `/name`
+    pub fn first_prompt(&self) -> Option<String> {
+        self.entries
+            .iter()
+            .find_map(|line| typed_prompt(&line.entry))
+    }
+
+    /// Human-readable session title: the summary title, or the truncated first prompt
+    pub fn title(&self) -> Option<String> {
+        self.summary_title()
+            .or_else(|| self.first_prompt())
+            .map(|title| truncate_title(&title, MAX_TITLE_LEN))
+    }
+
+    /// Find compaction points in the main conversation
+    ///
+    /// A compaction is marked by a `compact_boundary` system entry followed by a
+    /// user message flagged `isCompactSummary`. Older transcripts only contain the
+    /// summary message, which is then treated as the boundary itself.
+    pub fn compactions(&self) -> Vec<Compaction> {
+        let mut compactions: Vec<Compaction> = Vec::new();
+
+        for line in &self.entries {
+            let entry = &line.entry;
+            if entry.is_sidechain == Some(true) {
+                continue;
+            }
+
+            if entry.kind() == EntryKind::System
+                && entry.subtype.as_deref() == Some("compact_boundary")
+            {
+                let metadata = entry.compact_metadata.as_ref();
+                compactions.push(Compaction {
+                    line: line.line,
+                    timestamp: entry.timestamp.clone(),
+                    trigger: metadata.and_then(|m| m.trigger.clone()),
+                    pre_tokens: metadata.and_then(|m| m.pre_tokens),
+                    summary_line: None,
+                });
+            } else if entry.is_compact_summary == Some(true) {
+                match compactions.last_mut() {
+                    Some(last) if last.summary_line.is_none() => {
+                        last.summary_line = Some(line.line);
+                    }
+                    _ => compactions.push(Compaction {
+                        line: line.line,
+                        timestamp: entry.timestamp.clone(),
+                        summary_line: Some(line.line),
+                        ..Default::default()
+                    }),
+                }
+            }
+        }
+
+        compactions
+    }
+
+    /// Estimate context size growth between compactions
+    ///
+    /// Returns one segment per stretch of conversation, so there is always one
+    /// more segment than there are compactions.
+    pub fn context_segments(&self) -> Vec<ContextSegment> {
+        let boundaries: Vec<usize> = self.compactions().iter().map(|c| c.line).collect();
+        let mut segments = vec![ContextSegment::default(); boundaries.len() + 1];
+        let mut last_message_id: Option<&str> = None;
+
+        for line in &self.entries {
+            let entry = &line.entry;
+            if entry.kind() != EntryKind::Assistant || entry.is_sidechain == Some(true) {
+                continue;
+            }
+
+            let message = match &entry.message {
+                Some(message) => message,
+                None => continue,
+            };
+            let usage = match &message.usage {
+                Some(usage) => usage,
+                None => continue,
+            };
+
+            // A response with several content blocks is split over entries sharing one id
+            if message.id.is_some() && message.id.as_deref() == last_message_id {
+                continue;
+            }
+            last_message_id = message.id.as_deref();
+
+            let segment = boundaries.iter().filter(|&&b| b < line.line).count();
+            segments[segment].record(usage.context_tokens());
+        }
+
+        segments
+    }
+
+    /// Collect failed tool calls, user rejections and interrupted requests
+    ///
+    /// Each tool result is joined with the `tool_use` block that produced it so the
+    /// originating tool input can be reported.
+    pub fn tool_errors(&self) -> Vec<ToolError> {
+        let mut tool_uses: HashMap<&str, (&str, &Value)> = HashMap::new();
+        let mut errors = Vec::new();
+
+        for line in &self.entries {
+            let entry = &line.entry;
+            let message = match &entry.message {
+                Some(message) => message,
+                None => continue,
+            };
+
+            for block in &message.blocks {
+                match block {
+                    ContentBlock::ToolUse { id, name, input } => {
+                        tool_uses.insert(id.as_str(), (name.as_str(), input));
+                    }
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        is_error,
+                        ..
+                    } => {
+                        let text = block.text().unwrap_or_default();
+                        let kind = if text.starts_with(TOOL_REJECTED_PREFIX) {
+                            ToolErrorKind::Rejected
+                        } else if text.contains(INTERRUPTED_MARKER) {
+                            ToolErrorKind::Interrupted
+                        } else if *is_error == Some(true) {
+                            ToolErrorKind::Error
+                        } else {
+                            continue;
+                        };
+
+                        let tool_use = tool_uses.get(tool_use_id.as_str());
+                        errors.push(ToolError {
+                            line: line.line,
+                            timestamp: entry.timestamp.clone(),
+                            kind,
+                            tool_name: tool_use.map(|(name, _)| name.to_string()),
+                            tool_input: tool_use.map(|(_, input)| (*input).clone()),
+                            message: text,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            // Interruptions outside a tool call are written as a plain user message
+            if entry.kind() == EntryKind::User
+                && message
+                    .blocks
+                    .iter()
+                    .all(|b| !matches!(b, ContentBlock::ToolResult { .. }))
+                && message.content.trim_start().starts_with(INTERRUPTED_MARKER)
+            {
+                errors.push(ToolError {
+                    line: line.line,
+                    timestamp: entry.timestamp.clone(),
+                    kind: ToolErrorKind::Interrupted,
+                    tool_name: None,
+                    tool_input: None,
+                    message: message.content.trim().to_string(),
+                });
+            }
+        }
+
+        errors
+    }
+
+    /// Collect subagent launches and whether each one failed
+    pub fn task_calls(&self) -> Vec<TaskCall> {
+        let failed_ids: Vec<String> = self
+            .entries
+            .iter()
+            .filter_map(|line| line.entry.message.as_ref())
+            .flat_map(|message| &message.blocks)
+            .filter_map(|block| match block {
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    is_error: Some(true),
+                    ..
+                } => Some(tool_use_id.clone()),
+                _ => None,
+            })
+            .collect();
+
+        let mut calls = Vec::new();
+        for line in &self.entries {
+            let message = match &line.entry.message {
+                Some(message) => message,
+                None => continue,
+            };
+
+            for block in &message.blocks {
+                if let ContentBlock::ToolUse { id, name, input } = block {
+                    if !SUBAGENT_TOOLS.contains(&name.as_str()) {
+                        continue;
+                    }
+                    let field = |key: &str| {
+                        input
+                            .get(key)
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string()
+                    };
+                    calls.push(TaskCall {
+                        line: line.line,
+                        subagent_type: field("subagent_type"),
+                        prompt: field("prompt"),
+                        failed: failed_ids.contains(id),
+                    });
+                }
+            }
+        }
+
+        calls
+    }
+
+    /// Group sidechain entries into chains by following `parentUuid` links
+    pub fn sidechains(&self) -> Vec<Sidechain> {
+        let sidechain_entries: Vec<&SessionEntry> = self
+            .entries
+            .iter()
+            .map(|line| &line.entry)
+            .filter(|entry| entry.is_sidechain == Some(true))
+            .collect();
+
+        let parents: HashMap<&str, Option<&str>> = sidechain_entries
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .uuid
+                    .as_deref()
+                    .map(|uuid| (uuid, entry.parent_uuid.as_deref()))
+            })
+            .collect();
+
+        // Walk up to the first entry whose parent is not part of the sidechain
+        let root_of = |entry: &SessionEntry| -> Option<String> {
+            let mut current = entry.uuid.as_deref()?;
+            for _ in 0..parents.len() {
+                match parents.get(current).copied().flatten() {
+                    Some(parent) if parents.contains_key(parent) => current = parent,
+                    _ => break,
+                }
+            }
+            Some(current.to_string())
+        };
+
+        let mut chains: Vec<(String, Sidechain)> = Vec::new();
+        for entry in &sidechain_entries {
+            let root = match root_of(entry) {
+                Some(root) => root,
+                None => continue,
+            };
+
+            match chains.iter_mut().find(|(uuid, _)| *uuid == root) {
+                Some((_, chain)) => chain.length += 1,
+                None => chains.push((
+                    root,
+                    Sidechain {
+                        prompt: entry
+                            .message
+                            .as_ref()
+                            .map(|m| m.content.clone())
+                            .unwrap_or_default(),
+                        length: 1,
+                    },
+                )),
+            }
+        }
+
+        chains.into_iter().map(|(_, chain)| chain).collect()
+    }
+
+    /// Slash commands invoked by the user in the main conversation, in order
+    pub fn slash_commands(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|line| {
+                line.entry.kind() == EntryKind::User && line.entry.is_sidechain != Some(true)
+            })
+            .filter_map(|line| line.entry.message.as_ref())
+            .filter_map(|message| slash_command(&message.content))
+            .collect()
+    }
+
+    /// Print a warning to stderr if any lines failed to parse
+    pub fn warn_failures(&self) {
+        if !self.failures.is_empty() {
+            eprintln!(
+                "warning: skipped {} malformed line(s) in {} (run `claco session validate {}` for details)",
+                self.failures.len(),
+                self.path.display(),
+                self.session_id()
+            );
+        }
+    }
+}
+
+/// The prompt in an entry, if it is a message the user typed in the main conversation
+fn typed_prompt(entry: &SessionEntry) -> Option<String> {
+    if entry.kind() != EntryKind::User
+        || entry.is_sidechain == Some(true)
+        || entry.is_meta == Some(true)
+        || entry.is_compact_summary == Some(true)
+    {
+        return None;
+    }
+    let message = entry.message.as_ref().filter(|m| m.role == "user")?;
+    prompt_text(&message.content)
+}
+
+/// Extract the text a user typed from message content
+///
+/// Returns `None` for blank content and for the caveat Claude Code injects
+/// before local command output.
+fn prompt_text(content: &str) -> Option<String> {
+    let content = content.trim();
+    if content.is_empty()
+        || content.starts_with("Caveat: The messages below were generated by the user")
+    {
+        return None;
+    }
+
+    if let Some(command) = slash_command(content) {
+        return Some(command);
+    }
+
+    Some(content.to_string())
+}
+
+/// Extract the slash command from a `<command-name>` tag in message content
+///
+/// The returned name always starts with `/`.
+pub fn slash_command(content: &str) -> Option<String> {
+    let start = content.find("<command-name>")?;
+    let rest = &content[start + "<command-name>".len()..];
+    let end = rest.find("</command-name>")?;
+    let name = rest[..end].trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    if name.starts_with('/') {
+        Some(name.to_string())
+    } else {
+        Some(format!("/{name}"))
+    }
+}
+
+/// Collapse a title to its first line and cut it to `max_len` characters
+pub fn truncate_title(title: &str, max_len: usize) -> String {
+    let first_line = title.lines().next().unwrap_or_default().trim();
+    if first_line.chars().count() > max_len {
+        let truncated: String = first_line.chars().take(max_len - 3).collect();
+        format!("{truncated}...")
+    } else if title.trim().lines().count() > 1 {
+        format!("{first_line}...")
+    } else {
+        first_line.to_string()
+    }
+}
+
+/// A point in a session where the conversation was compacted
+#[derive(Debug, Clone, Default)]
+pub struct Compaction {
+    /// Line of the `compact_boundary` entry, or of the injected summary when no boundary was written
+    pub line: usize,
+    pub timestamp: Option<String>,
+    /// `auto` or `manual` when known
+    pub trigger: Option<String>,
+    /// Context size in tokens reported right before compaction
+    pub pre_tokens: Option<u64>,
+    /// Line of the user message carrying the compacted summary
+    pub summary_line: Option<usize>,
+}
+
+/// Context growth between two compactions, estimated from assistant usage
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextSegment {
+    /// Number of assistant requests in this segment
+    pub turns: usize,
+    pub start_tokens: Option<u64>,
+    pub end_tokens: Option<u64>,
+    pub peak_tokens: Option<u64>,
+}
+
+impl ContextSegment {
+    fn record(&mut self, tokens: u64) {
+        self.turns += 1;
+        self.start_tokens.get_or_insert(tokens);
+        self.end_tokens = Some(tokens);
+        self.peak_tokens = Some(self.peak_tokens.map_or(tokens, |peak| peak.max(tokens)));
+    }
+}
+
+/// Prefix of the tool result Claude Code writes when the user rejects a tool call
+const TOOL_REJECTED_PREFIX: &str = "The user doesn't want to proceed with this tool use";
+
+/// Marker Claude Code writes when the user interrupts a request
+const INTERRUPTED_MARKER: &str = "[Request interrupted by user";
+
+/// How a tool call or request failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ToolErrorKind {
+    /// The tool returned a result flagged `is_error`
+    Error,
+    /// The user rejected the tool call
+    Rejected,
+    /// The user interrupted the request
+    Interrupted,
+}
+
+impl ToolErrorKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ToolErrorKind::Error => "error",
+            ToolErrorKind::Rejected => "rejected",
+            ToolErrorKind::Interrupted => "interrupted",
+        }
+    }
+}
+
+/// A failed tool call or interrupted request found in a transcript
+#[derive(Debug, Clone)]
+pub struct ToolError {
+    pub line: usize,
+    pub timestamp: Option<String>,
+    pub kind: ToolErrorKind,
+    /// Name of the originating tool, if the failure belongs to a tool call
+    pub tool_name: Option<String>,
+    /// Input of the originating tool call
+    pub tool_input: Option<Value>,
+    /// Error text returned to the model
+    pub message: String,
+}
+
+/// Names of the tool Claude Code uses to launch subagents
+pub const SUBAGENT_TOOLS: &[&str] = &["Task", "Agent"];
+
+/// A subagent launched through the `Task` tool
+#[derive(Debug, Clone)]
+pub struct TaskCall {
+    pub line: usize,
+    pub subagent_type: String,
+    /// Prompt handed to the subagent, used to find its sidechain
+    pub prompt: String,
+    /// Whether the tool result was an error, rejection or interruption
+    pub failed: bool,
+}
+
+/// A chain of sidechain (subagent) messages
+#[derive(Debug, Clone)]
+pub struct Sidechain {
+    /// Prompt of the first message in the chain
+    pub prompt: String,
+    /// Number of messages in the chain
+    pub length: usize,
+}
+
+/// Parse the content of a session JSONL file, collecting failures per line
+pub fn parse_transcript(content: &str) -> (Vec<TranscriptLine>, Vec<ParseFailure>) {
+    let mut entries = Vec::new();
+    let mut failures = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_num = idx + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => {
+                failures.push(ParseFailure {
+                    line: line_num,
+                    error: format!("invalid JSON: {e}"),
+                });
+                continue;
+            }
+        };
+
+        match serde_json::from_value::<SessionEntry>(value) {
+            Ok(entry) => entries.push(TranscriptLine {
+                line: line_num,
+                entry,
+            }),
+            Err(e) => failures.push(ParseFailure {
+                line: line_num,
+                error: format!("unexpected entry schema: {e}"),
+            }),
+        }
+    }
+
+    (entries, failures)
+}
+
+/// Read a session JSONL file without failing on malformed lines
+pub fn read_transcript(path: &Path) -> Result<Transcript> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read session file: {}", path.display()))?;
+    let (entries, failures) = parse_transcript(&content);
+
+    Ok(Transcript {
+        path: path.to_path_buf(),
+        entries,
+        failures,
+    })
+}
+
+/// Get the ~/.claude/projects directory
+pub fn projects_dir() -> Result<PathBuf> {
+    Ok(claude_home()?.join("projects"))
+}
+
+/// List all project directories under ~/.claude/projects
+pub fn project_dirs() -> Result<Vec<PathBuf>> {
+    let projects_dir = projects_dir()?;
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(&projects_dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// List all session JSONL files in a project directory
+pub fn session_files(project_path: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(project_path)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Find the JSONL file of a session by ID across all projects
+pub fn find_session_file(session_id: &str) -> Result<Option<PathBuf>> {
+    for project_path in project_dirs()? {
+        let session_file = project_path.join(format!("{session_id}.jsonl"));
+        if session_file.exists() {
+            return Ok(Some(session_file));
+        }
+    }
+    Ok(None)
+}
+
+/// Find the most recently modified session file across all projects
+pub fn most_recent_session_file() -> Result<Option<PathBuf>> {
+    let mut most_recent: Option<(SystemTime, PathBuf)> = None;
+
+    for project_path in project_dirs()? {
+        for session_path in session_files(&project_path)? {
+            if let Ok(modified) = fs::metadata(&session_path).and_then(|m| m.modified()) {
+                if most_recent
+                    .as_ref()
+                    .is_none_or(|(time, _)| modified > *time)
+                {
+                    most_recent = Some((modified, session_path));
+                }
+            }
+        }
+    }
+
+    Ok(most_recent.map(|(_, path)| path))
+}
+
+/// Read the working directory recorded in a session file
+///
+/// Scans forward until an entry with a `cwd` is found, since transcripts may
+/// start with summary or bookkeeping lines that carry no working directory.
+/// Reading stops there, so only the start of a long session is read.
+pub fn session_cwd(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    read_cwd(BufReader::new(file))
+}
+
+/// The first `cwd` recorded in a JSONL stream
+fn read_cwd(reader: impl BufRead) -> Option<String> {
+    reader
+        .lines()
+        .map_while(|line| line.ok())
+        // Only lines mentioning the key are worth parsing
+        .filter(|line| line.contains("\"cwd\""))
+        .filter_map(|line| serde_json::from_str::<Value>(&line).ok())
+        .find_map(|value| value.get("cwd").and_then(|v| v.as_str()).map(String::from))
+}
+
+/// Title and working directory of a session, as listed by `claco projects`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionOverview {
+    pub title: Option<String>,
+    pub cwd: Option<String>,
+}
+
+/// Read a session's title and working directory in one buffered pass
+///
+/// Unlike [`read_transcript`], only summary lines, the first prompt and the
+/// first entry with a `cwd` are parsed. The title matches [`Transcript::title`].
+pub fn session_overview(path: &Path) -> Result<SessionOverview> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to read session file: {}", path.display()))?;
+    Ok(read_overview(BufReader::new(file)))
+}
+
+fn read_overview(reader: impl BufRead) -> SessionOverview {
+    // (leaf UUID, summary, whether the leaf is an entry of this session)
+    let mut summaries: Vec<(Option<String>, String, bool)> = Vec::new();
+    let mut first_prompt = None;
+    let mut cwd = None;
+
+    for line in reader.lines().map_while(|line| line.ok()) {
+        if line.contains("\"summary\"") {
+            let entry = serde_json::from_str::<SessionEntry>(&line).ok();
+            if let Some(entry) = entry.filter(|entry| entry.kind() == EntryKind::Summary) {
+                if let Some(summary) = entry.summary {
+                    summaries.push((entry.leaf_uuid, summary, false));
+                }
+                continue;
+            }
+        }
+
+        // Claude Code writes compact JSON, so a plain substring finds the UUID
+        for (leaf, _, own) in summaries.iter_mut().filter(|(_, _, own)| !*own) {
+            if let Some(leaf) = leaf {
+                *own = line.contains(&format!("\"uuid\":\"{leaf}\""));
+            }
+        }
+
+        let wants_prompt = first_prompt.is_none() && line.contains("\"user\"");
+        let wants_cwd = cwd.is_none() && line.contains("\"cwd\"");
+        if !wants_prompt && !wants_cwd {
+            continue;
+        }
+        let Ok(value) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if wants_cwd {
+            cwd = value.get("cwd").and_then(|v| v.as_str()).map(String::from);
+        }
+        if wants_prompt {
+            first_prompt = serde_json::from_value::<SessionEntry>(value)
+                .ok()
+                .and_then(|entry| typed_prompt(&entry));
+        }
+    }
+
+    let own_summary = summaries.iter().rev().find(|(_, _, own)| *own);
+    let title = own_summary
+        .or(summaries.first())
+        .map(|(_, summary, _)| summary.clone())
+        .or(first_prompt)
+        .map(|title| truncate_title(&title, MAX_TITLE_LEN));
+
+    SessionOverview { title, cwd }
+}
+
+/// Find the project directory whose sessions were recorded in the given working directory
+pub fn find_project_dir_for_cwd(cwd: &str) -> Result<Option<PathBuf>> {
+    for project_path in project_dirs()? {
+        for session_path in session_files(&project_path)? {
+            if session_cwd(&session_path).as_deref() == Some(cwd) {
+                return Ok(Some(project_path));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::EntryKind;
+
+    #[test]
+    fn test_parse_transcript_classifies_entries() {
+        let content = r#"{"type":"summary","summary":"Fix login bug","leafUuid":"abc"}
+{"type":"user","message":{"role":"user","content":"hello"},"cwd":"/tmp/x"}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"hi"}]}}
+{"type":"system","content":"Conversation compacted","subtype":"compact_boundary"}
+{"type":"file-history-snapshot","messageId":"m1","snapshot":{}}
+{"type":"queue-operation","operation":"enqueue"}
+
+{"foo":"bar"}
+"#;
+        let (entries, failures) = parse_transcript(content);
+        assert!(failures.is_empty());
+
+        let kinds: Vec<EntryKind> = entries.iter().map(|e| e.entry.kind()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                EntryKind::Summary,
+                EntryKind::User,
+                EntryKind::Assistant,
+                EntryKind::System,
+                EntryKind::FileHistorySnapshot,
+                EntryKind::Unknown("queue-operation".to_string()),
+                EntryKind::Unknown(String::new()),
+            ]
+        );
+        // Blank line is skipped but still counted for line numbers
+        assert_eq!(entries.last().unwrap().line, 8);
+    }
+
+    fn transcript_from(content: &str) -> Transcript {
+        let (entries, failures) = parse_transcript(content);
+        Transcript {
+            path: PathBuf::from("session.jsonl"),
+            entries,
+            failures,
+        }
+    }
+
+    #[test]
+    fn test_title_prefers_summary_for_this_session() {
+        let transcript = transcript_from(
+            r#"{"type":"summary","summary":"Earlier conversation","leafUuid":"elsewhere"}
+{"type":"summary","summary":"Fix login bug","leafUuid":"u2"}
+{"type":"user","uuid":"u1","message":{"role":"user","content":"please fix login"}}
+{"type":"assistant","uuid":"u2","message":{"role":"assistant","content":"done"}}
+"#,
+        );
+        assert_eq!(transcript.title().as_deref(), Some("Fix login bug"));
+    }
+
+    #[test]
+    fn test_title_falls_back_to_first_prompt() {
+        let transcript = transcript_from(
+            r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"Caveat: The messages below were generated by the user while running local commands."}}
+{"type":"user","message":{"role":"user","content":"<command-name>/review</command-name>\n<command-args></command-args>"}}
+"#,
+        );
+        assert_eq!(transcript.title().as_deref(), Some("/review"));
+
+        let long_prompt = "word ".repeat(30);
+        assert_eq!(truncate_title(&long_prompt, 20), "word word word wo...");
+        assert_eq!(truncate_title("first\nsecond", 20), "first...");
+    }
+
+    #[test]
+    fn test_compactions_and_context_segments() {
+        let transcript = transcript_from(
+            r#"{"type":"user","message":{"role":"user","content":"start"}}
+{"type":"assistant","message":{"id":"m1","role":"assistant","content":"a","usage":{"input_tokens":10,"cache_read_input_tokens":1000}}}
+{"type":"assistant","message":{"id":"m1","role":"assistant","content":"b","usage":{"input_tokens":10,"cache_read_input_tokens":1000}}}
+{"type":"assistant","message":{"id":"m2","role":"assistant","content":"c","usage":{"input_tokens":10,"cache_creation_input_tokens":5000,"cache_read_input_tokens":1000}}}
+{"type":"system","subtype":"compact_boundary","content":"Conversation compacted","compactMetadata":{"trigger":"auto","preTokens":6010}}
+{"type":"user","isCompactSummary":true,"message":{"role":"user","content":"This session is being continued..."}}
+{"type":"assistant","message":{"id":"m3","role":"assistant","content":"d","usage":{"input_tokens":800}}}
+{"type":"user","isCompactSummary":true,"message":{"role":"user","content":"This session is being continued..."}}
+"#,
+        );
+
+        let compactions = transcript.compactions();
+        assert_eq!(compactions.len(), 2);
+        assert_eq!(compactions[0].line, 5);
+        assert_eq!(compactions[0].trigger.as_deref(), Some("auto"));
+        assert_eq!(compactions[0].pre_tokens, Some(6010));
+        assert_eq!(compactions[0].summary_line, Some(6));
+        // Summary without a boundary entry still counts as a compaction
+        assert_eq!(compactions[1].line, 8);
+        assert_eq!(compactions[1].trigger, None);
+
+        let segments = transcript.context_segments();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(
+            segments[0],
+            ContextSegment {
+                turns: 2,
+                start_tokens: Some(1010),
+                end_tokens: Some(6010),
+                peak_tokens: Some(6010),
+            }
+        );
+        assert_eq!(segments[1].turns, 1);
+        assert_eq!(segments[1].start_tokens, Some(800));
+        assert_eq!(segments[2], ContextSegment::default());
+    }
+
+    #[test]
+    fn test_tool_errors() {
+        let transcript = transcript_from(
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test"}},{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"src/main.rs"}}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":"exit code 101"}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t2","is_error":true,"content":[{"type":"text","text":"The user doesn't want to proceed with this tool use. The tool use was rejected."}]}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t3","content":"ok"}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"text","text":"[Request interrupted by user]"}]}}
+"#,
+        );
+
+        let errors = transcript.tool_errors();
+        assert_eq!(errors.len(), 3);
+
+        assert_eq!(errors[0].kind, ToolErrorKind::Error);
+        assert_eq!(errors[0].tool_name.as_deref(), Some("Bash"));
+        assert_eq!(
+            errors[0].tool_input.as_ref().unwrap()["command"],
+            "cargo test"
+        );
+        assert_eq!(errors[0].message, "exit code 101");
+
+        assert_eq!(errors[1].kind, ToolErrorKind::Rejected);
+        assert_eq!(errors[1].tool_name.as_deref(), Some("Edit"));
+
+        assert_eq!(errors[2].kind, ToolErrorKind::Interrupted);
+        assert_eq!(errors[2].tool_name, None);
+        assert_eq!(errors[2].line, 5);
+    }
+
+    #[test]
+    fn test_task_calls_and_sidechains() {
+        let transcript = transcript_from(
+            r#"{"type":"assistant","uuid":"a1","isSidechain":false,"message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Task","input":{"subagent_type":"code-reviewer","prompt":"Review the diff"}},{"type":"tool_use","id":"t2","name":"Task","input":{"subagent_type":"tester","prompt":"Run tests"}}]}}
+{"type":"user","uuid":"s1","parentUuid":null,"isSidechain":true,"message":{"role":"user","content":"Review the diff"}}
+{"type":"assistant","uuid":"s2","parentUuid":"s1","isSidechain":true,"message":{"role":"assistant","content":"Looking"}}
+{"type":"user","uuid":"r1","parentUuid":null,"isSidechain":true,"message":{"role":"user","content":"Run tests"}}
+{"type":"assistant","uuid":"s3","parentUuid":"s2","isSidechain":true,"message":{"role":"assistant","content":"LGTM"}}
+{"type":"user","uuid":"u2","parentUuid":"a1","isSidechain":false,"message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"LGTM"},{"type":"tool_result","tool_use_id":"t2","is_error":true,"content":"failed"}]}}
+"#,
+        );
+
+        let calls = transcript.task_calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].subagent_type, "code-reviewer");
+        assert_eq!(calls[0].prompt, "Review the diff");
+        assert!(!calls[0].failed);
+        assert!(calls[1].failed);
+
+        let chains = transcript.sidechains();
+        assert_eq!(chains.len(), 2);
+        assert_eq!(chains[0].prompt, "Review the diff");
+        assert_eq!(chains[0].length, 3);
+        assert_eq!(chains[1].prompt, "Run tests");
+        assert_eq!(chains[1].length, 1);
+    }
+
+    #[test]
+    fn test_slash_commands() {
+        let transcript = transcript_from(
+            r#"{"type":"user","message":{"role":"user","content":"<command-message>review is running</command-message>\n<command-name>/review</command-name>"}}
+{"type":"user","message":{"role":"user","content":"<command-name>git:commit</command-name>"}}
+{"type":"user","isSidechain":true,"message":{"role":"user","content":"<command-name>/ignored</command-name>"}}
+{"type":"user","message":{"role":"user","content":"plain prompt"}}
+"#,
+        );
+        assert_eq!(
+            transcript.slash_commands(),
+            vec!["/review".to_string(), "/git:commit".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_transcript_reports_failures_with_line_numbers() {
+        let content = r#"{"type":"user","message":{"role":"user","content":"ok"}}
+{"type":"user","message":
+{"type":"user","message":{"role":"user","content":42}}
+"#;
+        let (entries, failures) = parse_transcript(content);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].line, 2);
+        assert!(failures[0].error.starts_with("invalid JSON"));
+        assert_eq!(failures[1].line, 3);
+        assert!(failures[1].error.starts_with("unexpected entry schema"));
+    }
+
+    #[test]
+    fn test_read_cwd_stops_at_first_match() {
+        let content = r#"{"type":"summary","summary":"Fix login bug","leafUuid":"u2"}
+{"type":"user","message":{"role":"user","content":"what is \"cwd\"?"}}
+{"type":"user","cwd":"/work/app","message":{"role":"user","content":"hi"}}
+{"type":"user","cwd":"/work/other","message":{"role":"user","content":"hi"}}
+"#;
+        assert_eq!(read_cwd(content.as_bytes()).as_deref(), Some("/work/app"));
+
+        // Nothing after the matching line is read
+        struct Unreadable;
+        impl std::io::Read for Unreadable {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                panic!("read past the first cwd");
+            }
+        }
+        let end = content
+            .find("{\"type\":\"user\",\"cwd\":\"/work/other")
+            .unwrap();
+        let reader = BufReader::new(std::io::Read::chain(&content.as_bytes()[..end], Unreadable));
+        assert_eq!(read_cwd(reader).as_deref(), Some("/work/app"));
+
+        assert_eq!(read_cwd(r#"{"type":"summary"}"#.as_bytes()), None);
+    }
+
+    #[test]
+    fn test_read_overview_matches_transcript() {
+        let fixtures = [
+            // Own summary wins over one carried over from an earlier session
+            r#"{"type":"summary","summary":"Earlier conversation","leafUuid":"elsewhere"}
+{"type":"summary","summary":"Fix login bug","leafUuid":"u2"}
+{"type":"user","uuid":"u1","cwd":"/work/app","message":{"role":"user","content":"please fix login"}}
+{"type":"assistant","uuid":"u2","cwd":"/work/app","message":{"role":"assistant","content":"done"}}
+"#,
+            // Without one of its own, the first summary is used
+            r#"{"type":"summary","summary":"Earlier conversation","leafUuid":"elsewhere"}
+{"type":"summary","summary":"Later conversation","leafUuid":"gone"}
+{"type":"user","uuid":"u1","message":{"role":"user","content":"hi"}}
+"#,
+            // No summary: the first typed prompt, skipping meta messages
+            r#"{"type":"user","isMeta":true,"cwd":"/work/app","message":{"role":"user","content":"Caveat: The messages below were generated by the user while running local commands."}}
+{"type":"user","cwd":"/work/app","message":{"role":"user","content":"<command-name>/review</command-name>"}}
+{"type":"user","cwd":"/work/app","message":{"role":"user","content":"second prompt"}}
+"#,
+            "",
+        ];
+
+        for content in fixtures {
+            let overview = read_overview(content.as_bytes());
+            let transcript = transcript_from(content);
+            assert_eq!(overview.title, transcript.title(), "{content}");
+            assert_eq!(overview.cwd, read_cwd(content.as_bytes()), "{content}");
+        }
+        assert_eq!(
+            read_overview(fixtures[0].as_bytes()),
+            SessionOverview {
+                title: Some("Fix login bug".to_string()),
+                cwd: Some("/work/app".to_string()),
+            }
+        );
+    }
+}